@@ -0,0 +1,110 @@
+//! # Error
+//!
+//! `error` contains the error type for every failure of the resource. Each variant carries a description of the failed operation and its cause, and is displayed with guidance for resolving it.
+
+// categories of resource failures
+#[derive(Eq, PartialEq, Debug)]
+pub(crate) enum Error {
+    // the credentials are invalid or lack permissions for the operation
+    Auth(String),
+    // the repository, issue, or comment does not exist
+    NotFound(String),
+    // the source or params are invalid, or github rejected the request contents
+    Validation(String),
+    // the github api rate limit was exceeded
+    RateLimit(String),
+    // github could not be reached
+    Network(String),
+    // github responded with an unexpected failure
+    Github(String),
+    // a file in the input or output directory could not be read or written
+    Io(String),
+}
+
+impl Error {
+    /// Constructor for an Error from a failed octocrab request, categorized by the response status. The context describes the operation that failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let error = Error::from_octocrab("the issue number 1 could not be retrieved", error);
+    /// ```
+    pub(crate) fn from_octocrab(context: &str, error: octocrab::Error) -> Self {
+        match error {
+            octocrab::Error::GitHub { source, .. } => {
+                let message = format!("{context}: {}", source.message);
+                match source.status_code.as_u16() {
+                    // github signals an exceeded primary rate limit with 403 and a secondary rate limit with 403 or 429
+                    403 | 429 if source.message.to_lowercase().contains("rate limit") => {
+                        Error::RateLimit(message)
+                    }
+                    401 | 403 => Error::Auth(message),
+                    404 => Error::NotFound(message),
+                    422 => Error::Validation(message),
+                    _ => Error::Github(message),
+                }
+            }
+            // the display of these variants includes a backtrace, and so only the source is reported
+            octocrab::Error::Service { source, .. } => {
+                Error::Network(format!("{context}: {source}"))
+            }
+            octocrab::Error::Hyper { source, .. } => Error::Network(format!("{context}: {source}")),
+            octocrab::Error::Http { source, .. } => Error::Network(format!("{context}: {source}")),
+            octocrab::Error::JWT { source, .. } => Error::Auth(format!("{context}: {source}")),
+            error => Error::Github(format!("{context}: {error}")),
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Auth(message) => write!(
+                f,
+                "authentication failed: {message}\nverify the pat, or the app_id, installation_id, and private_key, in source, and their permissions for the repository"
+            ),
+            Error::NotFound(message) => write!(
+                f,
+                "not found: {message}\nverify the owner, repo, and issue number, and that the credentials can access the repository"
+            ),
+            Error::Validation(message) => write!(f, "invalid configuration: {message}"),
+            Error::RateLimit(message) => write!(
+                f,
+                "rate limit exceeded: {message}\nwait for the rate limit to reset, or authenticate to increase the rate limit"
+            ),
+            Error::Network(message) => write!(
+                f,
+                "network failure: {message}\nverify that the github api (or the api_url in source) is reachable"
+            ),
+            Error::Github(message) => write!(f, "github api failure: {message}"),
+            Error::Io(message) => write!(f, "file failure: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_display() {
+        assert_eq!(
+            Error::Validation(String::from(
+                "the dedupe mode must be either title, labels, or marker"
+            ))
+            .to_string(),
+            String::from(
+                "invalid configuration: the dedupe mode must be either title, labels, or marker"
+            ),
+            "validation error not displayed with its message",
+        );
+        assert!(
+            Error::NotFound(String::from("the issue number 1 could not be retrieved"))
+                .to_string()
+                .contains("verify the owner, repo, and issue number"),
+            "not found error not displayed with guidance",
+        );
+    }
+}
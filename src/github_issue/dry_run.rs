@@ -0,0 +1,363 @@
+//! # Dry Run
+//!
+//! `dry_run` contains a backend for the dry run of the out/put step. Every read is performed by the wrapped backend, and every modification is instead printed as the Github API request it would have sent and applied to a synthetic revision of the issue.
+
+use std::cell::RefCell;
+
+use super::models::Models;
+use super::{Backend, Issue, ListFilter};
+use crate::error::Error;
+
+// the base urls of the synthetic urls unless the api url of a github enterprise server is specified
+const API_URL: &str = "https://api.github.com";
+const WEB_URL: &str = "https://github.com";
+// the number and id of everything the dry run would create, which github never assigns
+const SYNTHETIC: u64 = 0;
+
+// the backend that prints modifications instead of sending them
+pub(crate) struct DryRun<'a, B: Backend> {
+    backend: &'a B,
+    owner: String,
+    repo: String,
+    // the constructors of the synthetic models with the urls of the repository
+    models: Models,
+    // the synthetic revision of the created or updated issue
+    issue: RefCell<Option<octocrab::models::issues::Issue>>,
+}
+
+impl<'a, B: Backend> DryRun<'a, B> {
+    /// Constructor for the DryRun struct, which wraps the backend for the repository. The synthetic urls are derived from the api url of a github enterprise server if specified, and otherwise from api.github.com.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let dry_run = DryRun::new(&backend, "my_org", "my_repo", Some("https://github.example.com/api/v3"));
+    /// ```
+    pub(crate) fn new(backend: &'a B, owner: &str, repo: &str, api_url: Option<&str>) -> Self {
+        let api_url = api_url.unwrap_or(API_URL).trim_end_matches('/');
+        // github enterprise server serves the rest api beneath the web url
+        let web_url = match api_url {
+            API_URL => WEB_URL,
+            api_url => api_url.strip_suffix("/api/v3").unwrap_or(api_url),
+        };
+        DryRun {
+            backend,
+            owner: String::from(owner),
+            repo: String::from(repo),
+            models: Models::new(api_url, web_url, owner, repo),
+            issue: RefCell::new(None),
+        }
+    }
+
+    // a synthetic user with the input login
+    fn author(&self, login: &str) -> octocrab::models::Author {
+        self.models.author(SYNTHETIC, login)
+    }
+
+    // print the request that would have been sent to the repository route
+    fn request(&self, method: &str, route: &str, body: Option<serde_json::Value>) {
+        let body = body
+            .and_then(|body| serde_json::to_string_pretty(&body).ok())
+            .map(|body| format!("\n{body}"))
+            .unwrap_or_default();
+        eprintln!(
+            "dry run: the following request was not sent to the github api\n{method} /repos/{}/{}{route}{body}",
+            self.owner, self.repo
+        );
+    }
+
+    // apply the revision to the issue with the input number, and retain it as the synthetic revision
+    async fn revise(
+        &self,
+        number: u64,
+        revision: impl FnOnce(&mut octocrab::models::issues::Issue),
+    ) -> Result<octocrab::models::issues::Issue, Error> {
+        let mut issue = self.read(number).await?;
+        revision(&mut issue);
+        issue.updated_at = chrono::Utc::now();
+        self.issue.replace(Some(issue.clone()));
+
+        Ok(issue)
+    }
+
+    // the labels with the input names, which retain those already applied to the issue
+    fn revision_labels(
+        &self,
+        applied: &[octocrab::models::Label],
+        names: &[String],
+    ) -> Vec<octocrab::models::Label> {
+        names
+            .iter()
+            .map(|name| {
+                applied
+                    .iter()
+                    .find(|label| label.name.eq_ignore_ascii_case(name))
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        self.models
+                            .label(SYNTHETIC, name, super::DEFAULT_LABEL_COLOR, None)
+                    })
+            })
+            .collect()
+    }
+}
+
+// apply the members of the issue to the synthetic revision, except the milestone which is only printed in the request
+fn apply(
+    dry_run: &DryRun<'_, impl Backend>,
+    issue: &Issue,
+    revision: &mut octocrab::models::issues::Issue,
+) {
+    if let Some(title) = &issue.title {
+        revision.title = title.clone();
+    }
+    if let Some(body) = &issue.body {
+        revision.body = Some(body.clone());
+    }
+    if let Some(state) = &issue.state {
+        revision.state = state.clone();
+    }
+    if let Some(state_reason) = &issue.state_reason {
+        revision.state_reason = Some(state_reason.clone());
+    }
+    if let Some(labels) = &issue.labels {
+        revision.labels = dry_run.revision_labels(&revision.labels, labels);
+    }
+    if let Some(assignees) = &issue.assignees {
+        revision.assignees = assignees
+            .iter()
+            .map(|login| {
+                revision
+                    .assignees
+                    .iter()
+                    .find(|assignee| assignee.login.eq_ignore_ascii_case(login))
+                    .cloned()
+                    .unwrap_or_else(|| dry_run.author(login))
+            })
+            .collect();
+    }
+}
+
+impl<B: Backend> Backend for DryRun<'_, B> {
+    async fn create(&self, issue: &Issue) -> Result<octocrab::models::issues::Issue, Error> {
+        self.request("POST", "/issues", Some(super::create_body(issue)));
+
+        let mut revision = self.models.issue(
+            SYNTHETIC,
+            "",
+            None,
+            &self.author(&self.owner),
+            chrono::Utc::now(),
+        );
+        apply(self, issue, &mut revision);
+        self.issue.replace(Some(revision.clone()));
+
+        Ok(revision)
+    }
+
+    async fn read(&self, number: u64) -> Result<octocrab::models::issues::Issue, Error> {
+        // the synthetic revision supersedes the issue in the repository
+        if let Some(issue) = self
+            .issue
+            .borrow()
+            .as_ref()
+            .filter(|issue| issue.number == number)
+        {
+            return Ok(issue.clone());
+        }
+        self.backend.read(number).await
+    }
+
+    async fn list(
+        &self,
+        issue: &Issue,
+        filter: &ListFilter,
+    ) -> Result<Vec<octocrab::models::issues::Issue>, Error> {
+        self.backend.list(issue, filter).await
+    }
+
+    async fn find(
+        &self,
+        issue: &Issue,
+        filter: &ListFilter,
+        predicate: impl Fn(&octocrab::models::issues::Issue) -> bool,
+    ) -> Result<Option<octocrab::models::issues::Issue>, Error> {
+        self.backend.find(issue, filter, predicate).await
+    }
+
+    async fn update(
+        &self,
+        issue: &Issue,
+        number: u64,
+    ) -> Result<octocrab::models::issues::Issue, Error> {
+        self.request(
+            "PATCH",
+            &format!("/issues/{number}"),
+            Some(super::update_body(issue)),
+        );
+
+        self.revise(number, |revision| apply(self, issue, revision))
+            .await
+    }
+
+    async fn comment(
+        &self,
+        number: u64,
+        body: &str,
+    ) -> Result<octocrab::models::issues::Comment, Error> {
+        self.request(
+            "POST",
+            &format!("/issues/{number}/comments"),
+            Some(serde_json::json!({ "body": body })),
+        );
+        let issue = self.revise(number, |_| ()).await?;
+
+        Ok(self.models.comment(
+            SYNTHETIC,
+            number,
+            body,
+            &self.author(&self.owner),
+            issue.updated_at,
+        ))
+    }
+
+    async fn comments(&self, number: u64) -> Result<Vec<octocrab::models::issues::Comment>, Error> {
+        self.backend.comments(number).await
+    }
+
+    async fn read_comment(
+        &self,
+        comment_id: u64,
+    ) -> Result<octocrab::models::issues::Comment, Error> {
+        self.backend.read_comment(comment_id).await
+    }
+
+    async fn timeline(
+        &self,
+        number: u64,
+    ) -> Result<Vec<octocrab::models::timelines::TimelineEvent>, Error> {
+        self.backend.timeline(number).await
+    }
+
+    async fn is_org_member(&self, username: &str) -> Result<bool, Error> {
+        self.backend.is_org_member(username).await
+    }
+
+    async fn is_assignable(&self, username: &str) -> Result<bool, Error> {
+        self.backend.is_assignable(username).await
+    }
+
+    async fn add_labels(
+        &self,
+        number: u64,
+        labels: &[String],
+    ) -> Result<Vec<octocrab::models::Label>, Error> {
+        self.request(
+            "POST",
+            &format!("/issues/{number}/labels"),
+            Some(serde_json::json!({ "labels": labels })),
+        );
+        let issue = self
+            .revise(number, |revision| {
+                let added = labels
+                    .iter()
+                    .filter(|name| {
+                        !revision
+                            .labels
+                            .iter()
+                            .any(|label| label.name.eq_ignore_ascii_case(name))
+                    })
+                    .cloned()
+                    .collect::<Vec<String>>();
+                revision
+                    .labels
+                    .extend(self.revision_labels(&revision.labels, &added));
+            })
+            .await?;
+
+        Ok(issue.labels)
+    }
+
+    async fn remove_label(&self, number: u64, label: &str) -> Result<(), Error> {
+        let name = super::label_route_name(label);
+        self.request("DELETE", &format!("/issues/{number}/labels/{name}"), None);
+        // github rejects the removal of a label that is not applied to the issue
+        if !self
+            .read(number)
+            .await?
+            .labels
+            .iter()
+            .any(|applied| applied.name.eq_ignore_ascii_case(label))
+        {
+            return Err(Error::NotFound(format!(
+                "the label {label} could not be removed from the issue number {number}: Label does not exist"
+            )));
+        }
+        self.revise(number, |revision| {
+            revision
+                .labels
+                .retain(|applied| !applied.name.eq_ignore_ascii_case(label))
+        })
+        .await?;
+
+        Ok(())
+    }
+
+    async fn set_labels(
+        &self,
+        number: u64,
+        labels: &[String],
+    ) -> Result<Vec<octocrab::models::Label>, Error> {
+        self.request(
+            "PUT",
+            &format!("/issues/{number}/labels"),
+            Some(serde_json::json!({ "labels": labels })),
+        );
+        let issue = self
+            .revise(number, |revision| {
+                revision.labels = self.revision_labels(&revision.labels, labels);
+            })
+            .await?;
+
+        Ok(issue.labels)
+    }
+
+    async fn labels(&self) -> Result<Vec<octocrab::models::Label>, Error> {
+        self.backend.labels().await
+    }
+
+    async fn create_label(&self, name: &str, color: &str, description: &str) -> Result<(), Error> {
+        self.request(
+            "POST",
+            "/labels",
+            Some(serde_json::json!({
+                "name": name,
+                "color": color,
+                "description": description,
+            })),
+        );
+
+        Ok(())
+    }
+
+    async fn milestones(&self) -> Result<Vec<octocrab::models::Milestone>, Error> {
+        self.backend.milestones().await
+    }
+
+    async fn create_milestone(
+        &self,
+        title: &str,
+        due_on: Option<chrono::DateTime<chrono::Utc>>,
+        description: Option<&str>,
+    ) -> Result<octocrab::models::Milestone, Error> {
+        self.request(
+            "POST",
+            "/milestones",
+            Some(super::milestone_body(title, due_on, description)),
+        );
+
+        Ok(self
+            .models
+            .milestone(SYNTHETIC, title, description, due_on, chrono::Utc::now()))
+    }
+}
@@ -0,0 +1,658 @@
+//! # Fake
+//!
+//! `fake` contains an in-memory implementation of the issue backend for testing the resource steps offline and deterministically. The repository is seeded by the test, modified by the backend operations, and then inspected by the test.
+
+use std::cell::RefCell;
+
+use super::models::Models;
+use super::{Backend, Issue, ListFilter};
+use crate::error::Error;
+
+// the base url of every url in the fake repository
+const URL: &str = "https://github.example.com";
+// the owner and repo of the fake repository
+const OWNER: &str = "my_org";
+const REPO: &str = "my_repo";
+// the login of the authenticated user, who creates the issues and comments
+const LOGIN: &str = "my_user";
+// the creation time of the fake repository as a unix timestamp of 2024-01-01T00:00:00Z
+const EPOCH: i64 = 1_704_067_200;
+
+// the constructors of the models with the urls of the fake repository
+fn models() -> Models {
+    Models::new(URL, URL, OWNER, REPO)
+}
+
+// a user of the fake repository
+fn author(login: &str) -> octocrab::models::Author {
+    models().author(1, login)
+}
+
+// the state of the fake repository
+#[derive(Default)]
+struct Repository {
+    issues: Vec<octocrab::models::issues::Issue>,
+    // the comments and timeline events are paired with their issue number
+    comments: Vec<(u64, octocrab::models::issues::Comment)>,
+    events: Vec<(u64, octocrab::models::timelines::TimelineEvent)>,
+    labels: Vec<octocrab::models::Label>,
+    milestones: Vec<octocrab::models::Milestone>,
+    assignable: Vec<String>,
+    org_members: Vec<String>,
+    // seconds elapsed since the repository was created, which advances with every modification so that every revision is distinct
+    clock: i64,
+    // the id of the most recent comment or timeline event, which increase chronologically like github
+    id: u64,
+}
+
+impl Repository {
+    // advance the clock, and return the current time
+    fn now(&mut self) -> chrono::DateTime<chrono::Utc> {
+        self.clock += 1;
+        chrono::DateTime::from_timestamp(EPOCH + self.clock, 0).unwrap_or_default()
+    }
+
+    // advance the id, and return the next id
+    fn next_id(&mut self) -> u64 {
+        self.id += 1;
+        self.id
+    }
+
+    fn issue_mut(&mut self, number: u64) -> Result<&mut octocrab::models::issues::Issue, Error> {
+        self.issues
+            .iter_mut()
+            .find(|issue| issue.number == number)
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "the issue number {number} could not be retrieved from the repository: Not Found"
+                ))
+            })
+    }
+
+    // return the repository label with the input name, and create it with the default color like github if it does not exist
+    fn label(&mut self, name: &str) -> octocrab::models::Label {
+        if let Some(label) = self
+            .labels
+            .iter()
+            .find(|label| label.name.eq_ignore_ascii_case(name))
+        {
+            return label.clone();
+        }
+        let label = models().label(
+            self.labels.len() as u64 + 1,
+            name,
+            super::DEFAULT_LABEL_COLOR,
+            None,
+        );
+        self.labels.push(label.clone());
+        label
+    }
+
+    fn milestone(&self, number: u64) -> Result<octocrab::models::Milestone, Error> {
+        self.milestones
+            .iter()
+            .find(|milestone| milestone.number as u64 == number)
+            .cloned()
+            .ok_or_else(|| {
+                Error::Validation(format!(
+                    "the milestone number {number} does not exist in the repository: Validation Failed"
+                ))
+            })
+    }
+
+    // add a labeled or unlabeled event to the issue timeline
+    fn label_event(&mut self, number: u64, label: &str, added: bool) -> u64 {
+        let id = self.next_id();
+        let created_at = self.now();
+        let event = serde_json::from_value(serde_json::json!({
+            "event": if added { "labeled" } else { "unlabeled" },
+            "id": id,
+            "actor": author(LOGIN),
+            "created_at": created_at,
+            "label": { "name": label, "color": super::DEFAULT_LABEL_COLOR },
+        }))
+        .expect("the fake timeline event could not be constructed");
+        self.events.push((number, event));
+        id
+    }
+}
+
+// the in-memory backend for a single fake repository
+#[derive(Default)]
+pub(crate) struct Fake {
+    repository: RefCell<Repository>,
+}
+
+impl Fake {
+    /// Constructor for an empty fake repository.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let backend = Fake::new();
+    /// ```
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Seed an open issue with the input title, body, and labels, and return its number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let number = backend.add_issue("my issue", Some("my body"), &["bug"]);
+    /// ```
+    pub(crate) fn add_issue(&self, title: &str, body: Option<&str>, labels: &[&str]) -> u64 {
+        let issue = Issue::new(
+            Some(String::from(title)),
+            body.map(String::from),
+            Some(labels.iter().map(|label| String::from(*label)).collect()),
+            None,
+            None,
+            None,
+            None,
+            None,
+        )
+        .expect("the fake issue could not be constructed");
+        self.repository.borrow_mut().create(&issue).number
+    }
+
+    /// Seed a comment by the input user on the issue, and return its id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let comment_id = backend.add_comment(1, "my_user", "/approve");
+    /// ```
+    pub(crate) fn add_comment(&self, number: u64, login: &str, body: &str) -> u64 {
+        self.repository
+            .borrow_mut()
+            .comment(number, login, body)
+            .expect("the fake comment could not be constructed")
+            .id
+            .into_inner()
+    }
+
+    /// Seed a labeled, or otherwise unlabeled, event in the issue timeline, and return its id.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let event_id = backend.add_label_event(1, "approved", true);
+    /// ```
+    pub(crate) fn add_label_event(&self, number: u64, label: &str, added: bool) -> u64 {
+        self.repository
+            .borrow_mut()
+            .label_event(number, label, added)
+    }
+
+    /// Seed a user that can be assigned to issues in the repository.
+    pub(crate) fn add_assignable(&self, login: &str) {
+        self.repository
+            .borrow_mut()
+            .assignable
+            .push(String::from(login));
+    }
+
+    /// Seed a user that is a member of the organization that owns the repository.
+    pub(crate) fn add_org_member(&self, login: &str) {
+        self.repository
+            .borrow_mut()
+            .org_members
+            .push(String::from(login));
+    }
+
+    /// Seed a milestone in the repository, and return its number.
+    pub(crate) fn add_milestone(&self, title: &str) -> u64 {
+        self.repository
+            .borrow_mut()
+            .create_milestone(title, None, None)
+            .expect("the fake milestone could not be constructed")
+            .number as u64
+    }
+
+    /// Inspect the current revision of the issue with the input number.
+    pub(crate) fn issue(&self, number: u64) -> octocrab::models::issues::Issue {
+        self.repository
+            .borrow_mut()
+            .issue_mut(number)
+            .expect("the fake issue does not exist")
+            .clone()
+    }
+
+    /// Inspect every issue in the repository.
+    pub(crate) fn issues(&self) -> Vec<octocrab::models::issues::Issue> {
+        self.repository.borrow().issues.clone()
+    }
+
+    /// Inspect the comments on the issue with the input number.
+    pub(crate) fn issue_comments(&self, number: u64) -> Vec<octocrab::models::issues::Comment> {
+        self.repository
+            .borrow()
+            .comments
+            .iter()
+            .filter(|(issue, _)| *issue == number)
+            .map(|(_, comment)| comment.clone())
+            .collect()
+    }
+
+    /// Inspect every label in the repository.
+    pub(crate) fn repository_labels(&self) -> Vec<octocrab::models::Label> {
+        self.repository.borrow().labels.clone()
+    }
+
+    /// Inspect every milestone in the repository.
+    pub(crate) fn repository_milestones(&self) -> Vec<octocrab::models::Milestone> {
+        self.repository.borrow().milestones.clone()
+    }
+}
+
+impl Repository {
+    fn create(&mut self, issue: &Issue) -> octocrab::models::issues::Issue {
+        let number = self.issues.len() as u64 + 1;
+        let now = self.now();
+        let mut created = models().issue(
+            number,
+            &issue.title.clone().unwrap_or_default(),
+            issue.body.as_deref(),
+            &author(LOGIN),
+            now,
+        );
+        created.labels = issue
+            .labels
+            .iter()
+            .flatten()
+            .map(|label| self.label(label))
+            .collect();
+        created.assignees = issue
+            .assignees
+            .iter()
+            .flatten()
+            .map(|assignee| author(assignee))
+            .collect();
+        created.milestone = issue
+            .milestone
+            .and_then(|milestone| self.milestone(milestone).ok());
+        self.issues.push(created.clone());
+        created
+    }
+
+    fn comment(
+        &mut self,
+        number: u64,
+        login: &str,
+        body: &str,
+    ) -> Result<octocrab::models::issues::Comment, Error> {
+        let id = self.next_id();
+        let now = self.now();
+        let issue = self.issue_mut(number).map_err(|_| {
+            Error::NotFound(format!(
+                "a comment could not be created on the issue number {number}: Not Found"
+            ))
+        })?;
+        issue.comments += 1;
+        issue.updated_at = now;
+        let comment = models().comment(id, number, body, &author(login), now);
+        self.comments.push((number, comment.clone()));
+        Ok(comment)
+    }
+
+    fn create_milestone(
+        &mut self,
+        title: &str,
+        due_on: Option<chrono::DateTime<chrono::Utc>>,
+        description: Option<&str>,
+    ) -> Result<octocrab::models::Milestone, Error> {
+        if self
+            .milestones
+            .iter()
+            .any(|milestone| milestone.title == title)
+        {
+            return Err(Error::Validation(format!(
+                "the milestone {title} could not be created in the repository: Validation Failed"
+            )));
+        }
+        let number = self.milestones.len() as u64 + 1;
+        let now = self.now();
+        let milestone = models().milestone(number, title, description, due_on, now);
+        self.milestones.push(milestone.clone());
+        Ok(milestone)
+    }
+}
+
+impl Backend for Fake {
+    async fn create(&self, issue: &Issue) -> Result<octocrab::models::issues::Issue, Error> {
+        let mut repository = self.repository.borrow_mut();
+        if let Some(milestone) = issue.milestone {
+            repository.milestone(milestone)?;
+        }
+        Ok(repository.create(issue))
+    }
+
+    async fn read(&self, number: u64) -> Result<octocrab::models::issues::Issue, Error> {
+        Ok(self.repository.borrow_mut().issue_mut(number)?.clone())
+    }
+
+    async fn list(
+        &self,
+        issue: &Issue,
+        filter: &ListFilter,
+    ) -> Result<Vec<octocrab::models::issues::Issue>, Error> {
+        // github defaults to open issues if the state filter is omitted
+        let state = match (&filter.state, &issue.state) {
+            (Some(state), _) => {
+                super::str_to_params_state(state)?;
+                state.as_str()
+            }
+            (None, Some(octocrab::models::IssueState::Closed)) => "Closed",
+            (_, _) => "Open",
+        };
+        let since = filter.since()?;
+        let mut issues = self
+            .repository
+            .borrow()
+            .issues
+            .iter()
+            .filter(|candidate| {
+                state == "All" || super::issue_state_to_str(&candidate.state) == state
+            })
+            .filter(|candidate| {
+                issue.labels.iter().flatten().all(|label| {
+                    candidate
+                        .labels
+                        .iter()
+                        .any(|candidate_label| candidate_label.name.eq_ignore_ascii_case(label))
+                })
+            })
+            .filter(|candidate| {
+                issue.milestone.is_none_or(|milestone| {
+                    candidate
+                        .milestone
+                        .as_ref()
+                        .is_some_and(|candidate_milestone| {
+                            candidate_milestone.number as u64 == milestone
+                        })
+                })
+            })
+            .filter(|candidate| {
+                issue
+                    .assignees
+                    .as_ref()
+                    .and_then(|assignees| assignees.first())
+                    .is_none_or(|assignee| {
+                        candidate
+                            .assignees
+                            .iter()
+                            .any(|candidate_assignee| &candidate_assignee.login == assignee)
+                    })
+            })
+            .filter(|candidate| {
+                filter
+                    .creator
+                    .as_ref()
+                    .is_none_or(|creator| &candidate.user.login == creator)
+            })
+            .filter(|candidate| {
+                filter.mentioned.as_ref().is_none_or(|mentioned| {
+                    candidate
+                        .body
+                        .as_ref()
+                        .is_some_and(|body| body.contains(&format!("@{mentioned}")))
+                })
+            })
+            .filter(|candidate| since.is_none_or(|since| candidate.updated_at >= since))
+            .cloned()
+            .collect::<Vec<octocrab::models::issues::Issue>>();
+        // github sorts by descending creation unless otherwise specified
+        match filter.sort.as_deref() {
+            Some("updated") => issues.sort_by_key(|issue| issue.updated_at),
+            Some("comments") => issues.sort_by_key(|issue| issue.comments),
+            Some("created") | None => issues.sort_by_key(|issue| issue.created_at),
+            Some(sort) => {
+                super::str_to_sort(sort)?;
+            }
+        }
+        if filter.direction.as_deref() != Some("asc") {
+            filter
+                .direction
+                .as_deref()
+                .map(super::str_to_direction)
+                .transpose()?;
+            issues.reverse();
+        }
+
+        Ok(issues)
+    }
+
+    async fn find(
+        &self,
+        issue: &Issue,
+        filter: &ListFilter,
+        predicate: impl Fn(&octocrab::models::issues::Issue) -> bool,
+    ) -> Result<Option<octocrab::models::issues::Issue>, Error> {
+        Ok(self.list(issue, filter).await?.into_iter().find(predicate))
+    }
+
+    async fn update(
+        &self,
+        issue: &Issue,
+        number: u64,
+    ) -> Result<octocrab::models::issues::Issue, Error> {
+        let mut repository = self.repository.borrow_mut();
+        let milestone = issue
+            .milestone
+            .map(|milestone| repository.milestone(milestone))
+            .transpose()?;
+        let labels = issue.labels.as_ref().map(|labels| {
+            labels
+                .iter()
+                .map(|label| repository.label(label))
+                .collect::<Vec<octocrab::models::Label>>()
+        });
+        let now = repository.now();
+        let updated = repository.issue_mut(number).map_err(|_| {
+            Error::NotFound(format!(
+                "the issue number {number} could not be updated: Not Found"
+            ))
+        })?;
+        if let Some(title) = &issue.title {
+            updated.title = title.clone();
+        }
+        if let Some(body) = &issue.body {
+            updated.body = Some(body.clone());
+        }
+        if let Some(state) = &issue.state {
+            updated.closed_at = match state {
+                octocrab::models::IssueState::Closed => Some(now),
+                _ => None,
+            };
+            updated.state = state.clone();
+        }
+        if let Some(state_reason) = &issue.state_reason {
+            updated.state_reason = Some(state_reason.clone());
+        }
+        if milestone.is_some() {
+            updated.milestone = milestone;
+        }
+        if let Some(labels) = labels {
+            updated.labels = labels;
+        }
+        if let Some(assignees) = &issue.assignees {
+            updated.assignees = assignees.iter().map(|assignee| author(assignee)).collect();
+        }
+        updated.updated_at = now;
+
+        Ok(updated.clone())
+    }
+
+    async fn comment(
+        &self,
+        number: u64,
+        body: &str,
+    ) -> Result<octocrab::models::issues::Comment, Error> {
+        self.repository.borrow_mut().comment(number, LOGIN, body)
+    }
+
+    async fn comments(&self, number: u64) -> Result<Vec<octocrab::models::issues::Comment>, Error> {
+        self.read(number).await?;
+        Ok(self.issue_comments(number))
+    }
+
+    async fn read_comment(
+        &self,
+        comment_id: u64,
+    ) -> Result<octocrab::models::issues::Comment, Error> {
+        self.repository
+            .borrow()
+            .comments
+            .iter()
+            .map(|(_, comment)| comment)
+            .find(|comment| comment.id.into_inner() == comment_id)
+            .cloned()
+            .ok_or_else(|| {
+                Error::NotFound(format!(
+                    "the comment {comment_id} could not be retrieved from the repository: Not Found"
+                ))
+            })
+    }
+
+    async fn timeline(
+        &self,
+        number: u64,
+    ) -> Result<Vec<octocrab::models::timelines::TimelineEvent>, Error> {
+        self.read(number).await?;
+        Ok(self
+            .repository
+            .borrow()
+            .events
+            .iter()
+            .filter(|(issue, _)| *issue == number)
+            .map(|(_, event)| event.clone())
+            .collect())
+    }
+
+    async fn is_org_member(&self, username: &str) -> Result<bool, Error> {
+        Ok(self
+            .repository
+            .borrow()
+            .org_members
+            .iter()
+            .any(|member| member == username))
+    }
+
+    async fn is_assignable(&self, username: &str) -> Result<bool, Error> {
+        Ok(self
+            .repository
+            .borrow()
+            .assignable
+            .iter()
+            .any(|assignable| assignable == username))
+    }
+
+    async fn add_labels(
+        &self,
+        number: u64,
+        labels: &[String],
+    ) -> Result<Vec<octocrab::models::Label>, Error> {
+        let mut repository = self.repository.borrow_mut();
+        repository.issue_mut(number)?;
+        let mut added = Vec::new();
+        for label in labels {
+            let label = repository.label(label);
+            let issue = repository.issue_mut(number)?;
+            if !issue
+                .labels
+                .iter()
+                .any(|applied| applied.name == label.name)
+            {
+                issue.labels.push(label.clone());
+                added.push(label.name);
+            }
+        }
+        for label in added {
+            repository.label_event(number, &label, true);
+        }
+        let now = repository.now();
+        let issue = repository.issue_mut(number)?;
+        issue.updated_at = now;
+
+        Ok(issue.labels.clone())
+    }
+
+    async fn remove_label(&self, number: u64, label: &str) -> Result<(), Error> {
+        let mut repository = self.repository.borrow_mut();
+        let now = repository.now();
+        let issue = repository.issue_mut(number)?;
+        let Some(position) = issue
+            .labels
+            .iter()
+            .position(|applied| applied.name.eq_ignore_ascii_case(label))
+        else {
+            return Err(Error::NotFound(format!(
+                "the label {label} could not be removed from the issue number {number}: Label does not exist"
+            )));
+        };
+        let removed = issue.labels.remove(position);
+        issue.updated_at = now;
+        repository.label_event(number, &removed.name, false);
+
+        Ok(())
+    }
+
+    async fn set_labels(
+        &self,
+        number: u64,
+        labels: &[String],
+    ) -> Result<Vec<octocrab::models::Label>, Error> {
+        let mut repository = self.repository.borrow_mut();
+        repository.issue_mut(number)?;
+        let labels = labels
+            .iter()
+            .map(|label| repository.label(label))
+            .collect::<Vec<octocrab::models::Label>>();
+        let now = repository.now();
+        let issue = repository.issue_mut(number)?;
+        issue.labels = labels;
+        issue.updated_at = now;
+
+        Ok(issue.labels.clone())
+    }
+
+    async fn labels(&self) -> Result<Vec<octocrab::models::Label>, Error> {
+        Ok(self.repository_labels())
+    }
+
+    async fn create_label(&self, name: &str, color: &str, description: &str) -> Result<(), Error> {
+        let mut repository = self.repository.borrow_mut();
+        if repository
+            .labels
+            .iter()
+            .any(|label| label.name.eq_ignore_ascii_case(name))
+        {
+            return Err(Error::Validation(format!(
+                "the label {name} could not be created in the repository: Validation Failed"
+            )));
+        }
+        let id = repository.labels.len() as u64 + 1;
+        repository
+            .labels
+            .push(models().label(id, name, color, Some(description)));
+
+        Ok(())
+    }
+
+    async fn milestones(&self) -> Result<Vec<octocrab::models::Milestone>, Error> {
+        Ok(self.repository_milestones())
+    }
+
+    async fn create_milestone(
+        &self,
+        title: &str,
+        due_on: Option<chrono::DateTime<chrono::Utc>>,
+        description: Option<&str>,
+    ) -> Result<octocrab::models::Milestone, Error> {
+        self.repository
+            .borrow_mut()
+            .create_milestone(title, due_on, description)
+    }
+}
@@ -0,0 +1,189 @@
+//! # Models
+//!
+//! `models` contains the constructors of octocrab models in the Github REST API response format. These construct the synthetic revisions of a dry run, and the issues of the fake backend and the recorded responses in the tests.
+
+use chrono::{DateTime, Utc};
+
+// the base urls of a github instance and the repository from which every url of the models is derived
+pub(crate) struct Models {
+    api_url: String,
+    web_url: String,
+    // the api and web urls of the repository
+    repo_api_url: String,
+    repo_web_url: String,
+}
+
+impl Models {
+    /// Constructor for the Models struct. The api and web urls are those of the github instance such as api.github.com and github.com, and the owner and repo are those of the repository.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let models = Models::new("https://github.example.com/api/v3", "https://github.example.com", "my_org", "my_repo");
+    /// ```
+    pub(crate) fn new(api_url: &str, web_url: &str, owner: &str, repo: &str) -> Self {
+        Self {
+            api_url: String::from(api_url),
+            web_url: String::from(web_url),
+            repo_api_url: format!("{api_url}/repos/{owner}/{repo}"),
+            repo_web_url: format!("{web_url}/{owner}/{repo}"),
+        }
+    }
+
+    /// Construct the user with the input id and login.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let user = models.author(1, "my_user");
+    /// ```
+    pub(crate) fn author(&self, id: u64, login: &str) -> octocrab::models::Author {
+        let (api_url, web_url) = (&self.api_url, &self.web_url);
+        serde_json::from_value(serde_json::json!({
+            "login": login,
+            "id": id,
+            "node_id": "",
+            "avatar_url": format!("{web_url}/{login}.png"),
+            "gravatar_id": "",
+            "url": format!("{api_url}/users/{login}"),
+            "html_url": format!("{web_url}/{login}"),
+            "followers_url": format!("{api_url}/users/{login}/followers"),
+            "following_url": format!("{api_url}/users/{login}/following"),
+            "gists_url": format!("{api_url}/users/{login}/gists"),
+            "starred_url": format!("{api_url}/users/{login}/starred"),
+            "subscriptions_url": format!("{api_url}/users/{login}/subscriptions"),
+            "organizations_url": format!("{api_url}/users/{login}/orgs"),
+            "repos_url": format!("{api_url}/users/{login}/repos"),
+            "events_url": format!("{api_url}/users/{login}/events"),
+            "received_events_url": format!("{api_url}/users/{login}/received_events"),
+            "type": "User",
+            "site_admin": false,
+        }))
+        .expect("the user is a valid github user")
+    }
+
+    /// Construct the open issue with the input number, title, body, and creator, and without any labels, assignees, milestone, or comments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let issue = models.issue(1, "my issue", Some("my body"), &models.author(1, "my_user"), chrono::Utc::now());
+    /// ```
+    pub(crate) fn issue(
+        &self,
+        number: u64,
+        title: &str,
+        body: Option<&str>,
+        user: &octocrab::models::Author,
+        created_at: DateTime<Utc>,
+    ) -> octocrab::models::issues::Issue {
+        let issue_url = format!("{}/issues/{number}", self.repo_api_url);
+        serde_json::from_value(serde_json::json!({
+            "id": number,
+            "node_id": "",
+            "url": issue_url,
+            "repository_url": self.repo_api_url,
+            "labels_url": format!("{issue_url}/labels"),
+            "comments_url": format!("{issue_url}/comments"),
+            "events_url": format!("{issue_url}/events"),
+            "html_url": format!("{}/issues/{number}", self.repo_web_url),
+            "number": number,
+            "state": "open",
+            "title": title,
+            "body": body,
+            "user": user,
+            "labels": [],
+            "assignees": [],
+            "locked": false,
+            "comments": 0,
+            "created_at": created_at,
+            "updated_at": created_at,
+        }))
+        .expect("the issue is a valid github issue")
+    }
+
+    /// Construct the repository label with the input id, name, color, and description.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let label = models.label(1, "bug", "d73a4a", Some("something is broken"));
+    /// ```
+    pub(crate) fn label(
+        &self,
+        id: u64,
+        name: &str,
+        color: &str,
+        description: Option<&str>,
+    ) -> octocrab::models::Label {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "node_id": "",
+            "url": format!("{}/labels/{name}", self.repo_api_url),
+            "name": name,
+            "description": description,
+            "color": color,
+            "default": false,
+        }))
+        .expect("the label is a valid github label")
+    }
+
+    /// Construct the comment with the input id and body by the user on the issue with the input number.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let comment = models.comment(1, 5, "/approve", &models.author(1, "my_user"), chrono::Utc::now());
+    /// ```
+    pub(crate) fn comment(
+        &self,
+        id: u64,
+        number: u64,
+        body: &str,
+        user: &octocrab::models::Author,
+        created_at: DateTime<Utc>,
+    ) -> octocrab::models::issues::Comment {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "node_id": "",
+            "url": format!("{}/issues/comments/{id}", self.repo_api_url),
+            "html_url": format!("{}/issues/{number}#issuecomment-{id}", self.repo_web_url),
+            "issue_url": format!("{}/issues/{number}", self.repo_api_url),
+            "body": body,
+            "user": user,
+            "created_at": created_at,
+            "updated_at": created_at,
+        }))
+        .expect("the comment is a valid github comment")
+    }
+
+    /// Construct the open milestone with the input number, title, description, and due date.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let milestone = models.milestone(1, "v1.0", None, None, chrono::Utc::now());
+    /// ```
+    pub(crate) fn milestone(
+        &self,
+        number: u64,
+        title: &str,
+        description: Option<&str>,
+        due_on: Option<DateTime<Utc>>,
+        created_at: DateTime<Utc>,
+    ) -> octocrab::models::Milestone {
+        serde_json::from_value(serde_json::json!({
+            "url": format!("{}/milestones/{number}", self.repo_api_url),
+            "html_url": format!("{}/milestone/{number}", self.repo_web_url),
+            "id": number,
+            "node_id": "",
+            "number": number,
+            "state": "open",
+            "title": title,
+            "description": description,
+            "created_at": created_at,
+            "due_on": due_on,
+        }))
+        .expect("the milestone is a valid github milestone")
+    }
+}
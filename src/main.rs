@@ -1,7 +1,14 @@
 use concourse_resource::*;
 
 mod concourse;
+mod error;
 mod github_issue;
+mod retry;
+mod template;
+#[cfg(test)]
+mod test_dir;
+
+use error::Error;
 
 struct GithubIssue {}
 
@@ -9,137 +16,818 @@ impl concourse_resource::Resource for GithubIssue {
     // implementtations for inputs and outputs
     type Source = concourse::Source;
     type Version = concourse::Version;
-    type InParams = concourse_resource::Empty;
-    type InMetadata = concourse_resource::Empty;
+    type InParams = concourse::InParams;
+    type InMetadata = concourse::InMetadata;
     type OutParams = concourse::OutParams;
     type OutMetadata = concourse::OutMetadata;
 
     // implementations for steps
-    /// Performs the check step for the resource. Returns a single sized vector of version of state string if the input issue is Open (no trigger), and a two sized vector of version of state string if the input issue is closed (trigger). For convenience and standardization the former return is "Open", and the latter is "Open" and "Closed".
+    /// Performs the check step for the resource. If a query is specified in the source, then returns one version per matching issue ordered from least to most recently updated. If a comment trigger is specified in the source, then returns one version per matching comment on the issue ordered from oldest to newest. If a label trigger is specified in the source, then returns one version per matching label event on the issue ordered from oldest to newest. If a revision trigger is specified in the source, then returns a single sized vector with the version of the current revision of the issue, which is keyed by the issue number, state, and last update time. Otherwise returns a size two vector for an issue closed since the input version and a size one vector for an open issue or an issue that was already closed, so that only the closure of the issue triggers.
     #[tokio::main]
     async fn resource_check(
         source: Option<Self::Source>,
-        _version: Option<Self::Version>,
+        version: Option<Self::Version>,
     ) -> Vec<Self::Version> {
+        Self::check(source, version)
+            .await
+            .unwrap_or_else(|error| Self::fail(error))
+    }
+
+    /// Performs the in step for the resource. Reads the Github issue specified in the version or source, and writes its data to files in the output directory for consumption by subsequent tasks. If no issue number is specified in the version or source, then this step is skipped.
+    #[tokio::main]
+    async fn resource_in(
+        source: Option<Self::Source>,
+        version: Self::Version,
+        params: Option<Self::InParams>,
+        output_path: &str,
+    ) -> Result<
+        concourse_resource::InOutput<Self::Version, Self::InMetadata>,
+        Box<dyn std::error::Error>,
+    > {
+        // the version issue number from a query check takes precedence over the source issue number
+        let number = version
+            .number()
+            .or(source.as_ref().and_then(|source| source.number()));
+        // if no number is specified then there is no issue to materialize
+        let (Some(source), Some(number)) = (source, number) else {
+            eprintln!(
+                "no issue number was specified in the version or source, and therefore the in step is skipped"
+            );
+            return Ok(concourse_resource::InOutput {
+                version,
+                metadata: None,
+            });
+        };
+
+        source.validate()?;
+        let backend = Self::backend(&source);
+        Ok(Self::get_with(&backend, &source, number, version, params, output_path).await?)
+    }
+
+    /// Performs the out step for the resource. Creates a new Github issue based on the parameters, or updates an existing Github issue if an issue number is specified in the params or source. Optionally adds a comment to the created or updated issue. If a dry run is specified in the params, then every modification is printed instead of sent.
+    #[tokio::main]
+    async fn resource_out(
+        source: Option<Self::Source>,
+        params: Option<Self::OutParams>,
+        input_path: &str,
+    ) -> concourse_resource::OutOutput<Self::Version, Self::OutMetadata> {
+        Self::out(source, params, input_path)
+            .await
+            .unwrap_or_else(|error| Self::fail(error))
+    }
+}
+
+// helper functions if we need them
+impl GithubIssue {
+    // report the failure to stderr and exit unsuccessfully, because concourse interprets stdout as the step output
+    fn fail(error: Error) -> ! {
+        eprintln!("Error! {error}");
+        std::process::exit(1);
+    }
+
+    // the github api backend for the repository of the source
+    fn backend(source: &concourse::Source) -> github_issue::Github {
+        github_issue::Github::new(source.client_config(), source.owner(), source.repo())
+    }
+
+    // perform the check step, and return the versions or the failure
+    async fn check(
+        source: Option<concourse::Source>,
+        version: Option<concourse::Version>,
+    ) -> Result<Vec<concourse::Version>, Error> {
         // validate and re-assign source
-        let source = match source {
-            Some(source) => source,
-            None => panic!("source is required for the Github Issue resource"),
+        let Some(source) = source else {
+            return Err(Error::Validation(String::from(
+                "source is required for the Github Issue resource",
+            )));
         };
+        source.validate()?;
+        let backend = Self::backend(&source);
+        Self::check_with(&backend, &source, version).await
+    }
+
+    // perform the check step with the input backend, and return the versions or the failure
+    async fn check_with(
+        backend: &impl github_issue::Backend,
+        source: &concourse::Source,
+        version: Option<concourse::Version>,
+    ) -> Result<Vec<concourse::Version>, Error> {
+        // if a query is specified in source then every matching issue is checked instead of a single issue
+        if let Some(query) = source.query() {
+            return Self::check_query(backend, source, query, version).await;
+        }
 
         // if no number is specified in source then this resource execution should skip the check step and cannot trigger
         if source.number().is_none() {
-            println!(
+            eprintln!(
                 "no issue number was specified in source, and therefore the check step is skipped"
             );
-            return vec![concourse::Version::new(String::from("Open"))];
+            return Ok(vec![concourse::Version::new(String::from("Open"))]);
+        }
+
+        // if a comment trigger is specified in source then the comments on the issue are checked instead of its revisions
+        if let Some(comment_trigger) = source.comment_trigger() {
+            return Self::check_comments(backend, source, comment_trigger, version).await;
+        }
+        // if a label trigger is specified in source then the label events on the issue are checked instead of its revisions
+        if let Some(label_trigger) = source.label_trigger() {
+            return Self::check_labels(backend, source, label_trigger, version).await;
         }
 
         // construct an issue...
-        let gh_issue = github_issue::Issue::new(
-            source.pat(),
-            source.owner(),
-            source.repo(),
-            None,
-            None,
-            None,
-            None,
-            source.number(),
-            None,
-            None,
-        );
+        let gh_issue =
+            github_issue::Issue::new(None, None, None, None, source.number(), None, None, None)?;
         // ...and read the octocrab github issue
-        let issue = match gh_issue.main(github_issue::Action::Read).await {
-            Ok(issue) => issue,
-            Err(error) => {
-                println!("{error}");
-                panic!("the check step was unable to read the specified github issue number");
-            }
-        };
+        let issue = gh_issue
+            .main(backend, github_issue::Action::Read)
+            .await?
+            .issue()?;
 
-        // return one sized version vector if issue is open and two sized if closed
-        match issue.state {
-            octocrab::models::IssueState::Open => vec![concourse::Version::new(String::from("Open"))],
-            octocrab::models::IssueState::Closed => vec![concourse::Version::new(String::from("Open")), concourse::Version::new(String::from("Closed"))],
-            _ => panic!("expected the github issue state to either be open or closed, and somehow it is something else")
+        // if a revision trigger is specified in source then every revision of the issue is checked instead of only its closure
+        if source.revision_trigger() {
+            // the current revision of the issue is the only version that can be retrieved
+            let current = concourse::Version::from_issue(&issue);
+            return match version {
+                // the input version is still the current revision, and so there are no new versions
+                Some(version) if version == current => Ok(vec![version]),
+                // otherwise the issue changed since the input version (or this is the first check), and the intermediate revisions are unavailable
+                _ => Ok(vec![current]),
+            };
+        }
+
+        // otherwise the versions are keyed by the issue state so that only the closure of the issue triggers
+        let current = concourse::Version::from_state(issue.number, &issue.state);
+        match (version, &issue.state) {
+            // the input version is still the current state, and so there are no new versions
+            (Some(version), _) if version == current => Ok(vec![version]),
+            // the closure of the issue is a delta of versions from Open to Closed
+            (_, octocrab::models::IssueState::Closed) => Ok(vec![
+                concourse::Version::from_state(issue.number, &octocrab::models::IssueState::Open),
+                current,
+            ]),
+            _ => Ok(vec![current]),
         }
     }
 
-    /// Dummies the in step as it performs no functionality.
-    #[tokio::main]
-    async fn resource_in(
-        _source: Option<Self::Source>,
-        _version: Self::Version,
-        _params: Option<Self::InParams>,
-        _output_path: &str,
-    ) -> Result<
-        concourse_resource::InOutput<Self::Version, Self::InMetadata>,
-        Box<dyn std::error::Error>,
-    > {
+    // perform the in/get step for the issue number with the input backend, and return the output or the failure
+    async fn get_with(
+        backend: &impl github_issue::Backend,
+        source: &concourse::Source,
+        number: u64,
+        version: concourse::Version,
+        params: Option<concourse::InParams>,
+        output_path: &str,
+    ) -> Result<concourse_resource::InOutput<concourse::Version, concourse::InMetadata>, Error>
+    {
+        // construct an issue...
+        let gh_issue =
+            github_issue::Issue::new(None, None, None, None, Some(number), None, None, None)?;
+        // ...and read the octocrab github issue
+        let issue = gh_issue
+            .main(backend, github_issue::Action::Read)
+            .await?
+            .issue()?;
+        // the github api only serves the current revision of an issue
+        if version.updated_at().is_some_and(|updated_at| {
+            Some(updated_at) != concourse::Version::from_issue(&issue).updated_at()
+        }) {
+            eprintln!(
+                "the issue number {number} was updated since the requested version, and so the current revision is retrieved instead"
+            );
+        }
+
+        // write the issue data to files in the output directory
+        Self::write_issue_files(output_path, &issue)?;
+        // ...and the triggering comment from a comment trigger check
+        if let Some(comment_id) = version.comment() {
+            let comment = backend.read_comment(comment_id).await?;
+            let pattern = source
+                .comment_trigger()
+                .map(Self::comment_pattern)
+                .transpose()?;
+            Self::write_trigger_comment_files(output_path, &comment, pattern.as_ref())?;
+        }
+        // ...and optionally the issue comments
+        if params.is_some_and(|params| params.comments()) {
+            let comments = gh_issue.comments(backend).await?;
+            Self::write_comment_files(output_path, &comments)?;
+        }
+
         Ok(concourse_resource::InOutput {
-            version: concourse::Version::new(String::from("Open")),
-            metadata: None,
+            version,
+            metadata: Some(concourse::InMetadata::new(
+                issue.number,
+                issue.title,
+                String::from(github_issue::issue_state_to_str(&issue.state)),
+                issue.html_url.to_string(),
+            )),
         })
     }
 
-    /// Performs the out step for the resource. Creates a new Github issue based on the parameters.
-    #[tokio::main]
-    async fn resource_out(
-        source: Option<Self::Source>,
-        params: Option<Self::OutParams>,
+    // perform the out/put step, and return the output or the failure
+    async fn out(
+        source: Option<concourse::Source>,
+        params: Option<concourse::OutParams>,
         input_path: &str,
-    ) -> concourse_resource::OutOutput<Self::Version, Self::OutMetadata> {
+    ) -> Result<concourse_resource::OutOutput<concourse::Version, concourse::OutMetadata>, Error>
+    {
         // validate source and params
-        let source = match source {
-            Some(source) => source,
-            None => panic!("source is required for the Github Issue resource"),
+        let Some(source) = source else {
+            return Err(Error::Validation(String::from(
+                "source is required for the Github Issue resource",
+            )));
         };
-        let params = match params {
-            Some(params) => params,
-            None => panic!("params is required for the Github Issue resource out/put step"),
+        let Some(params) = params else {
+            return Err(Error::Validation(String::from(
+                "params is required for the Github Issue resource out/put step",
+            )));
+        };
+        source.validate()?;
+        let backend = Self::backend(&source);
+        // a dry run performs every lookup with the backend, but only prints the modifications
+        if params.dry_run() {
+            let dry_run = github_issue::dry_run::DryRun::new(
+                &backend,
+                &source.owner(),
+                &source.repo(),
+                source.api_url().as_deref(),
+            );
+            return Self::out_with(&dry_run, &source, params, input_path).await;
+        }
+        Self::out_with(&backend, &source, params, input_path).await
+    }
+
+    // perform the out/put step with the input backend, and return the output or the failure
+    async fn out_with(
+        backend: &impl github_issue::Backend,
+        source: &concourse::Source,
+        params: concourse::OutParams,
+        input_path: &str,
+    ) -> Result<concourse_resource::OutOutput<concourse::Version, concourse::OutMetadata>, Error>
+    {
+        // render the title and body templates before any issue is found, created, or updated, and otherwise read them from their files
+        let title = match (params.title(), params.title_file()) {
+            (Some(title), _) => Some(template::render(&title, input_path)?),
+            (None, Some(title_file)) => Some(
+                Self::read_input_file(input_path, &title_file)?
+                    .trim()
+                    .to_string(),
+            ),
+            (None, None) => None,
+        };
+        let body = match (params.body(), params.body_file()) {
+            (Some(body), _) => Some(template::render(&body, input_path)?),
+            (None, Some(body_file)) => Some(Self::read_input_file(input_path, &body_file)?),
+            (None, None) => None,
+        };
+        let labels = match (params.labels(), params.labels_file()) {
+            (Some(labels), _) => Some(labels),
+            (None, Some(labels_file)) => Some(Self::read_input_list(input_path, &labels_file)?),
+            (None, None) => None,
+        };
+        let assignees = match (params.assignees(), params.assignees_file()) {
+            (Some(assignees), _) => Some(assignees),
+            (None, Some(assignees_file)) => {
+                Some(Self::read_input_list(input_path, &assignees_file)?)
+            }
+            (None, None) => None,
+        };
+        // resolve the comment body before any issue is created or updated
+        let comment = match (params.comment(), params.comment_file()) {
+            (Some(comment), _) => Some(comment),
+            (None, Some(comment_file)) => Some(Self::read_input_file(input_path, &comment_file)?),
+            (None, None) => None,
+        };
+
+        let dedupe = params
+            .dedupe()
+            .map(|dedupe| github_issue::str_to_dedupe(&dedupe, params.marker()))
+            .transpose()?;
+        // params issue number (or the number read from its file) takes precedence over source issue number
+        let params_number = match (params.number(), params.number_file()) {
+            (Some(number), _) => Some(number),
+            (None, Some(number_file)) => Some(Self::read_input_number(input_path, &number_file)?),
+            (None, None) => None,
+        };
+        let mut number = params_number.or(source.number());
+        // otherwise search for an existing open issue if deduplication is enabled
+        let mut deduplicated = false;
+        if let (None, Some(dedupe)) = (number, &dedupe) {
+            // deduplicating by labels without any labels would match every open issue
+            if *dedupe == github_issue::Dedupe::Labels
+                && labels.as_ref().is_none_or(|labels| labels.is_empty())
+            {
+                return Err(Error::Validation(String::from(
+                    "labels must be specified to deduplicate issues by labels",
+                )));
+            }
+            let gh_issue = github_issue::Issue::new(
+                title.clone(),
+                None,
+                labels.clone(),
+                None,
+                None,
+                None,
+                None,
+                None,
+            )?;
+            number = gh_issue
+                .find(backend, dedupe)
+                .await?
+                .map(|existing| existing.number);
+            deduplicated = number.is_some();
+        }
+        // the existing issue keeps its title, body, labels, assignees, and milestone because they may have been triaged since it was created
+        let (title, body, labels, assignees, milestone) = match deduplicated {
+            true => (None, None, None, None, None),
+            false => (
+                title,
+                body,
+                labels,
+                assignees,
+                // the params milestone takes precedence over the source milestone
+                params.milestone().or(source.milestone()),
+            ),
+        };
+
+        // a hidden marker is embedded in a created or rewritten body so the issue can be found again
+        let body = match (&dedupe, body) {
+            (Some(github_issue::Dedupe::Marker(marker)), Some(body)) => Some(format!(
+                "{body}\n\n{}",
+                github_issue::marker_comment(marker)
+            )),
+            (Some(github_issue::Dedupe::Marker(marker)), None) if number.is_none() => {
+                Some(github_issue::marker_comment(marker))
+            }
+            (_, body) => body,
+        };
+
+        // create the missing labels before any are applied to the issue
+        if let Some(create_labels) = params.create_labels() {
+            let missing = [labels.clone(), params.add_labels(), params.set_labels()]
+                .into_iter()
+                .flatten()
+                .flatten()
+                .collect::<Vec<String>>();
+            github_issue::create_missing_labels(
+                backend,
+                &missing,
+                create_labels.color().as_deref(),
+                create_labels.description().as_deref(),
+            )
+            .await?;
+        }
+
+        // resolve the milestone title to its number
+        let milestone =
+            Self::resolve_milestone(backend, milestone, params.create_milestone()).await?;
+
+        // a dry run does not modify the issue, and so it outputs the version of the current revision to avoid triggering downstream jobs
+        let current = match (number, params.dry_run()) {
+            (Some(number), true) => Some(backend.read(number).await?),
+            _ => None,
         };
 
         // construct an issue...
         let gh_issue = github_issue::Issue::new(
-            source.pat(),
-            source.owner(),
-            source.repo(),
-            Some(params.title()),
-            params.body(),
-            params.labels(),
-            params.assignees(),
-            None,
-            None,
-            source.milestone(),
-        );
-        // ...and create the octocrab github issue
-        let issue = match gh_issue.main(github_issue::Action::Create).await {
-            Ok(issue) => issue,
-            Err(error) => {
-                println!("{error}");
-                panic!("the out/put step was unable to create the associated github issue");
-            }
+            title,
+            body,
+            labels,
+            assignees,
+            number,
+            params.state().as_deref(),
+            params.state_reason().as_deref(),
+            milestone,
+        )?;
+        // update the issue if a number was specified, and otherwise create a new issue
+        // an issue without any change is only read so that a user who cannot edit the issue may still label or comment on it
+        let action = match number {
+            Some(_) if gh_issue.has_changes() => github_issue::Action::Update,
+            Some(_) => github_issue::Action::Read,
+            None => github_issue::Action::Create,
         };
+        // ...and create, update, or read the octocrab github issue
+        let mut issue = gh_issue.main(backend, action).await?.issue()?;
 
-        // store issue number in file for subsequent check step
-        let file_path = format!("{input_path}/issue_number.txt");
-        std::fs::write(file_path, issue.number.to_string())
-            .expect("issue number could not be written to {file_path}");
+        // replace, add, and then remove labels with the label endpoints so labels applied by others are preserved, and then add the comment
+        let (set_labels, add_labels, remove_labels) = (
+            params.set_labels(),
+            params.add_labels(),
+            params.remove_labels(),
+        );
+        let gh_issue =
+            github_issue::Issue::new(None, None, None, None, Some(issue.number), None, None, None)?;
+        if let Some(set_labels) = &set_labels {
+            gh_issue.set_labels(backend, set_labels).await?;
+        }
+        if let Some(add_labels) = &add_labels {
+            gh_issue.add_labels(backend, add_labels).await?;
+        }
+        if let Some(remove_labels) = &remove_labels {
+            gh_issue.remove_labels(backend, remove_labels).await?;
+        }
+        let comment = match comment {
+            Some(comment) => Some(gh_issue.comment(backend, &comment).await?),
+            None => None,
+        };
+        // the label operations and the comment produce a new revision of the issue
+        if set_labels.is_some()
+            || add_labels.is_some()
+            || remove_labels.is_some()
+            || comment.is_some()
+        {
+            issue = gh_issue
+                .main(backend, github_issue::Action::Read)
+                .await?
+                .issue()?;
+        }
 
-        // return out step output
-        concourse_resource::OutOutput {
-            version: concourse::Version::new(String::from("Open")),
+        // return out step output with the version of the issue revision so the implicit get step retrieves the created or updated issue
+        // the issue created by a dry run does not exist, and so its version has no number and the implicit get step is skipped
+        // concourse still records the version, which is the version that the check step returns for a source without a number
+        let version = match (current, params.dry_run()) {
+            (Some(current), _) => concourse::Version::from_issue(&current),
+            (None, true) => concourse::Version::new(String::from(
+                github_issue::issue_state_to_str(&issue.state),
+            )),
+            (None, false) => concourse::Version::from_issue(&issue),
+        };
+        Ok(concourse_resource::OutOutput {
+            version,
             metadata: Some(concourse::OutMetadata::new(
                 issue.number,
+                issue.title,
+                String::from(github_issue::issue_state_to_str(&issue.state)),
+                issue.html_url.to_string(),
                 issue.labels,
                 issue.assignees,
+                comment.as_ref().map(|comment| comment.id.into_inner()),
+                comment.map(|comment| comment.html_url.to_string()),
             )),
+        })
+    }
+
+    // check every comment on the source issue matching the comment trigger, and return the versions from the input version onward
+    async fn check_comments(
+        backend: &impl github_issue::Backend,
+        source: &concourse::Source,
+        comment_trigger: &concourse::CommentTrigger,
+        version: Option<concourse::Version>,
+    ) -> Result<Vec<concourse::Version>, Error> {
+        let pattern = Self::comment_pattern(comment_trigger)?;
+        // construct an issue...
+        let gh_issue =
+            github_issue::Issue::new(None, None, None, None, source.number(), None, None, None)?;
+        // ...and read every octocrab github issue comment
+        let comments = gh_issue.comments(backend).await?;
+
+        // resume from the input version, which is itself included if it is still valid
+        let resume = version.and_then(|version| version.comment());
+        let users = comment_trigger.users();
+        let restricted = !users.is_empty() || comment_trigger.org_members();
+        // membership is only determined once per commenter
+        let mut members = std::collections::HashMap::new();
+        let mut matches = Vec::new();
+        for comment in comments {
+            if resume.is_some_and(|resume| comment.id.into_inner() < resume)
+                || !pattern.is_match(comment.body.as_deref().unwrap_or_default())
+            {
+                continue;
+            }
+            let login = comment.user.login.clone();
+            let allowed = !restricted
+                || users.contains(&login)
+                || (comment_trigger.org_members()
+                    && match members.get(&login) {
+                        Some(member) => *member,
+                        None => {
+                            // an undeterminable membership is reported and treated as a non-member
+                            let member = match backend.is_org_member(&login).await {
+                                Ok(member) => member,
+                                Err(error) => {
+                                    eprintln!("{error}");
+                                    false
+                                }
+                            };
+                            members.insert(login.clone(), member);
+                            member
+                        }
+                    });
+            if allowed {
+                matches.push(comment);
+            }
         }
+
+        // comment ids increase chronologically
+        matches.sort_by_key(|comment| comment.id);
+        let number = source.number().unwrap_or_default();
+        let versions = match resume {
+            Some(_) => matches
+                .iter()
+                .map(|comment| concourse::Version::from_comment(number, comment))
+                .collect(),
+            // the first check only returns the most recent matching comment
+            None => matches
+                .last()
+                .map(|comment| concourse::Version::from_comment(number, comment))
+                .into_iter()
+                .collect(),
+        };
+
+        Ok(versions)
     }
-}
 
-// helper functions if we need them
-impl GithubIssue {}
+    // check every event in the source issue timeline for the label trigger, and return the versions from the input version onward
+    async fn check_labels(
+        backend: &impl github_issue::Backend,
+        source: &concourse::Source,
+        label_trigger: &concourse::LabelTrigger,
+        version: Option<concourse::Version>,
+    ) -> Result<Vec<concourse::Version>, Error> {
+        // construct an issue...
+        let gh_issue =
+            github_issue::Issue::new(None, None, None, None, source.number(), None, None, None)?;
+        // ...and read every octocrab github issue timeline event
+        let events = gh_issue.timeline(backend).await?;
+
+        // resume from the input version, which is itself included if it is still valid
+        let resume = version.and_then(|version| version.event());
+        let label = label_trigger.label();
+        // timeline events are already in chronological order
+        let matches = events
+            .iter()
+            .filter(|event| match event.event {
+                octocrab::models::Event::Labeled => true,
+                octocrab::models::Event::Unlabeled => label_trigger.removed(),
+                _ => false,
+            })
+            .filter(|event| {
+                event
+                    .label
+                    .as_ref()
+                    .is_some_and(|event_label| event_label.name == label)
+            })
+            .filter_map(|event| event.id.map(|id| id.into_inner()))
+            .filter(|id| resume.is_none_or(|resume| *id >= resume))
+            .collect::<Vec<u64>>();
+
+        let number = source.number().unwrap_or_default();
+        let versions = match resume {
+            Some(_) => matches
+                .iter()
+                .map(|id| concourse::Version::from_event(number, *id))
+                .collect(),
+            // the first check only returns the most recent matching label event
+            None => matches
+                .last()
+                .map(|id| concourse::Version::from_event(number, *id))
+                .into_iter()
+                .collect(),
+        };
+
+        Ok(versions)
+    }
+
+    // check every issue matching the source query, and return the versions from the input version onward
+    async fn check_query(
+        backend: &impl github_issue::Backend,
+        source: &concourse::Source,
+        query: &concourse::Query,
+        version: Option<concourse::Version>,
+    ) -> Result<Vec<concourse::Version>, Error> {
+        let milestone = Self::resolve_milestone(backend, source.milestone(), None).await?;
+        // construct an issue with the label, milestone, and assignee filters...
+        let gh_issue = github_issue::Issue::new(
+            None,
+            None,
+            query.labels(),
+            query.assignee().map(|assignee| vec![assignee]),
+            None,
+            None,
+            None,
+            milestone,
+        )?;
+        // ...and the remaining filters with only issues updated since the input version
+        let since = version.as_ref().and_then(|version| version.updated_at());
+        let filter = github_issue::ListFilter::new(
+            query.state(),
+            query.creator(),
+            query.mentioned(),
+            since.clone(),
+            Some(String::from("updated")),
+            Some(String::from("asc")),
+        );
+        let mut issues = gh_issue
+            .main(backend, github_issue::Action::List(filter))
+            .await?
+            .issues();
+
+        // pull requests are also returned by the issues api
+        issues.retain(|issue| issue.pull_request.is_none());
+        // order issues from least to most recently updated with the number as a tiebreaker
+        issues.sort_by_key(|issue| (issue.updated_at, issue.number));
+
+        // resume from the input version, which is itself included if it is still valid
+        let resume = match (since, version.and_then(|version| version.number())) {
+            (Some(since), Some(number)) => since
+                .parse::<chrono::DateTime<chrono::Utc>>()
+                .ok()
+                .map(|since| (since, number)),
+            _ => None,
+        };
+        let versions = match resume {
+            Some(resume) => issues
+                .iter()
+                .filter(|issue| (issue.updated_at, issue.number) >= resume)
+                .map(concourse::Version::from_issue)
+                .collect(),
+            // the first check only returns the most recently updated issue
+            None => issues
+                .last()
+                .map(concourse::Version::from_issue)
+                .into_iter()
+                .collect(),
+        };
+
+        Ok(versions)
+    }
+
+    // resolve the milestone number from its number or title, and optionally create the milestone if no milestone has the title
+    async fn resolve_milestone(
+        backend: &impl github_issue::Backend,
+        milestone: Option<concourse::Milestone>,
+        create_milestone: Option<&concourse::CreateMilestone>,
+    ) -> Result<Option<u64>, Error> {
+        let title = match milestone {
+            Some(concourse::Milestone::Title(title)) => title,
+            Some(concourse::Milestone::Number(number)) => return Ok(Some(number)),
+            None => return Ok(None),
+        };
+        if let Some(number) = github_issue::find_milestone(backend, &title).await? {
+            return Ok(Some(number));
+        }
+        match create_milestone {
+            Some(create_milestone) => Ok(Some(
+                github_issue::create_milestone(
+                    backend,
+                    &title,
+                    create_milestone.due_on().as_deref(),
+                    create_milestone.description().as_deref(),
+                )
+                .await?,
+            )),
+            None => Err(Error::NotFound(format!(
+                "the milestone {title} does not exist in the repository"
+            ))),
+        }
+    }
+
+    // read the contents of a file relative to the input path of the out/put step
+    fn read_input_file(input_path: &str, file: &str) -> Result<String, Error> {
+        let file_path = format!("{input_path}/{file}");
+        std::fs::read_to_string(&file_path)
+            .map_err(|error| Error::Io(format!("the file {file_path} could not be read: {error}")))
+    }
+
+    // read a list with one entry per line from a file relative to the input path of the out/put step
+    fn read_input_list(input_path: &str, file: &str) -> Result<Vec<String>, Error> {
+        Ok(Self::read_input_file(input_path, file)?
+            .lines()
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(String::from)
+            .collect())
+    }
+
+    // read an issue number from a file relative to the input path of the out/put step
+    fn read_input_number(input_path: &str, file: &str) -> Result<u64, Error> {
+        let contents = Self::read_input_file(input_path, file)?;
+        contents.trim().parse::<u64>().map_err(|_| {
+            Error::Validation(format!(
+                "the file {input_path}/{file} does not contain an issue number: {}",
+                contents.trim()
+            ))
+        })
+    }
+
+    // write the contents of a file relative to the output path of the in/get step
+    fn write_output_file(output_path: &str, file: &str, contents: String) -> Result<(), Error> {
+        let file_path = format!("{output_path}/{file}");
+        std::fs::write(&file_path, contents).map_err(|error| {
+            Error::Io(format!(
+                "the file {file_path} could not be written: {error}"
+            ))
+        })
+    }
+
+    // serialize the input to pretty printed json for an output file of the in/get step
+    fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> Result<String, Error> {
+        serde_json::to_string_pretty(value)
+            .map_err(|error| Error::Io(format!("the json output could not be serialized: {error}")))
+    }
+
+    // compile the comment trigger pattern
+    fn comment_pattern(comment_trigger: &concourse::CommentTrigger) -> Result<regex::Regex, Error> {
+        let pattern = comment_trigger.pattern();
+        regex::Regex::new(&pattern).map_err(|error| {
+            Error::Validation(format!(
+                "the comment trigger pattern {pattern} is not a valid regular expression: {error}"
+            ))
+        })
+    }
+
+    // write the issue data to individual files in the output path of the in/get step
+    fn write_issue_files(
+        output_path: &str,
+        issue: &octocrab::models::issues::Issue,
+    ) -> Result<(), Error> {
+        // labels and assignees are newline delimited for convenient shell consumption
+        let labels = issue
+            .labels
+            .iter()
+            .map(|label| label.name.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let assignees = issue
+            .assignees
+            .iter()
+            .map(|assignee| assignee.login.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n");
+        let files = [
+            ("issue.json", Self::to_json(issue)?),
+            ("number", issue.number.to_string()),
+            ("title", issue.title.clone()),
+            ("body", issue.body.clone().unwrap_or_default()),
+            (
+                "state",
+                String::from(github_issue::issue_state_to_str(&issue.state)),
+            ),
+            ("labels", labels),
+            ("assignees", assignees),
+            ("url", issue.html_url.to_string()),
+        ];
+        for (file, contents) in files {
+            Self::write_output_file(output_path, file, contents)?;
+        }
+
+        Ok(())
+    }
+
+    // write the comment that triggered the check step, and the capture groups of the trigger pattern, to files in the output path of the in/get step
+    fn write_trigger_comment_files(
+        output_path: &str,
+        comment: &octocrab::models::issues::Comment,
+        pattern: Option<&regex::Regex>,
+    ) -> Result<(), Error> {
+        let body = comment.body.clone().unwrap_or_default();
+        // capture groups are newline delimited for convenient shell consumption
+        let captures = pattern
+            .and_then(|pattern| pattern.captures(&body))
+            .map(|captures| {
+                captures
+                    .iter()
+                    .skip(1)
+                    .map(|capture| capture.map_or("", |capture| capture.as_str()))
+                    .collect::<Vec<&str>>()
+                    .join("\n")
+            })
+            .unwrap_or_default();
+        let files = [
+            ("comment.json", Self::to_json(comment)?),
+            ("comment", body.clone()),
+            ("comment_author", comment.user.login.clone()),
+            ("comment_captures", captures),
+        ];
+        for (file, contents) in files {
+            Self::write_output_file(output_path, file, contents)?;
+        }
+
+        Ok(())
+    }
+
+    // write the issue comments to a json file and individual markdown files in the output path of the in/get step
+    fn write_comment_files(
+        output_path: &str,
+        comments: &[octocrab::models::issues::Comment],
+    ) -> Result<(), Error> {
+        Self::write_output_file(output_path, "comments.json", Self::to_json(comments)?)?;
+        std::fs::create_dir_all(format!("{output_path}/comments")).map_err(|error| {
+            Error::Io(format!(
+                "the directory {output_path}/comments could not be created: {error}"
+            ))
+        })?;
+        for comment in comments {
+            Self::write_output_file(
+                output_path,
+                &format!("comments/{}.md", comment.id),
+                comment.body.clone().unwrap_or_default(),
+            )?;
+        }
+
+        Ok(())
+    }
+}
 // macro to populate the concourse functions
 concourse_resource::create_resource!(GithubIssue);
 
@@ -147,52 +835,878 @@ concourse_resource::create_resource!(GithubIssue);
 mod tests {
     use super::*;
 
+    // deserialize the source from its concourse pipeline json input
+    fn source(source_input: &str) -> concourse::Source {
+        serde_json::from_str::<concourse::Source>(source_input)
+            .expect("source could not be deserialized")
+    }
+
+    // deserialize the out params from their concourse pipeline json input
+    fn out_params(params_input: &str) -> concourse::OutParams {
+        serde_json::from_str::<concourse::OutParams>(params_input)
+            .expect("out params could not be deserialized")
+    }
+
+    #[test]
+    fn test_resource_check_conflicting_triggers() {
+        // validate the check step rejects a source with more than one trigger before any request
+        let source = source(
+            r#"
+{
+    "owner": "my_org",
+    "repo": "my_repo",
+    "number": 1,
+    "comment_trigger": {"pattern": "^/deploy"},
+    "label_trigger": {"label": "deploy"}
+}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        assert!(
+            matches!(
+                rt.block_on(GithubIssue::check(Some(source), None)),
+                Err(Error::Validation(_))
+            ),
+            "the check step did not reject the comment and label triggers"
+        );
+    }
+
     #[test]
     fn test_resource_check() {
-        // validate basic check reading from mitodl/ol-infrastructure issue 1
-        // concourse pipeline json input
-        let source_input = r#"
+        // validate basic check reading from the fake issue 1
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &[]);
+        let source = source(
+            r#"
 {
-    "owner": "mitodl",
-    "repo": "ol-infrastructure",
+    "owner": "my_org",
+    "repo": "my_repo",
     "number": 1
-}"#;
-        let version_input = r#"
+}"#,
+        );
+        let open = concourse::Version::from_state(1, &octocrab::models::IssueState::Open);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let version_vec = rt
+            .block_on(GithubIssue::check_with(&backend, &source, None))
+            .expect("the check step failed");
+        // the open issue does not trigger
+        assert_eq!(
+            version_vec,
+            vec![concourse::Version::from_state(
+                1,
+                &octocrab::models::IssueState::Open
+            )],
+            "the resource_check did not return only the open version for the open issue",
+        );
+        // an update of the open issue is not a new version
+        backend.add_comment(1, "my_user", "looks good");
+        let updated = rt
+            .block_on(GithubIssue::check_with(
+                &backend,
+                &source,
+                version_vec.into_iter().next(),
+            ))
+            .expect("the check step failed");
+        assert_eq!(
+            updated,
+            vec![concourse::Version::from_state(
+                1,
+                &octocrab::models::IssueState::Open
+            )],
+            "the resource_check returned a new version for the updated open issue",
+        );
+        // the closed issue triggers with the closed version after the open version
+        let gh_issue =
+            github_issue::Issue::new(None, None, None, None, Some(1), Some("Closed"), None, None)
+                .unwrap();
+        rt.block_on(gh_issue.main(&backend, github_issue::Action::Update))
+            .expect("the issue could not be closed");
+        let closed = rt
+            .block_on(GithubIssue::check_with(&backend, &source, Some(open)))
+            .expect("the check step failed");
+        assert_eq!(
+            closed,
+            vec![
+                concourse::Version::from_state(1, &octocrab::models::IssueState::Open),
+                concourse::Version::from_state(1, &octocrab::models::IssueState::Closed)
+            ],
+            "the resource_check did not return the closed version after the open version for the closed issue",
+        );
+        // the issue that was already closed at the input version does not trigger again
+        let already_closed = rt
+            .block_on(GithubIssue::check_with(
+                &backend,
+                &source,
+                closed.into_iter().last(),
+            ))
+            .expect("the check step failed");
+        assert_eq!(
+            already_closed,
+            vec![concourse::Version::from_state(
+                1,
+                &octocrab::models::IssueState::Closed
+            )],
+            "the resource_check did not return only the input version for the issue that was already closed",
+        );
+    }
+
+    #[test]
+    fn test_check_revisions() {
+        // validate every revision of the issue is checked with a revision trigger
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &[]);
+        let source = source(
+            r#"
 {
-    "state": "Closed"
-}"#;
-        // deserialize version and source for inputs
-        let source = serde_json::from_str::<<GithubIssue as concourse_resource::Resource>::Source>(
-            source_input,
-        )
-        .expect("source could not be deserialized");
-        let version =
-            serde_json::from_str::<<GithubIssue as concourse_resource::Resource>::Version>(
-                version_input,
-            )
+    "owner": "my_org",
+    "repo": "my_repo",
+    "number": 1,
+    "revision_trigger": true
+}"#,
+        );
+        let version = serde_json::from_str::<concourse::Version>(r#"{"state": "Closed"}"#)
             .expect("version could not be deserialized");
-        let version_vec = GithubIssue::resource_check(Some(source), Some(version));
-        // the issue is closed so we expect a size two vec
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let version_vec = rt
+            .block_on(GithubIssue::check_with(&backend, &source, Some(version)))
+            .expect("the check step failed");
+        // the issue has changed since the input version so we expect its current revision
         assert_eq!(
-                version_vec,
-                vec![concourse::Version::new(String::from("Open")), concourse::Version::new(String::from("Closed"))],
-                "the resource_check did not return a two size vector of issue states for a closed issue",
-            );
+            version_vec,
+            vec![concourse::Version::from_issue(&backend.issue(1))],
+            "the check did not return the version of the current issue revision",
+        );
+        // the current revision is not a new version
+        let current = rt
+            .block_on(GithubIssue::check_with(
+                &backend,
+                &source,
+                version_vec.into_iter().next(),
+            ))
+            .expect("the check step failed");
+        assert_eq!(
+            current,
+            vec![concourse::Version::from_issue(&backend.issue(1))],
+            "the check did not return the input version for the unchanged issue",
+        );
+        // an update of the issue is a new version
+        backend.add_comment(1, "my_user", "looks good");
+        let updated = rt
+            .block_on(GithubIssue::check_with(
+                &backend,
+                &source,
+                current.into_iter().next(),
+            ))
+            .expect("the check step failed");
+        assert_eq!(
+            updated,
+            vec![concourse::Version::from_issue(&backend.issue(1))],
+            "the check did not return the version of the updated issue revision",
+        );
     }
 
     #[test]
-    fn test_resource_in() {
-        let in_output = GithubIssue::resource_in(
+    fn test_check_query() {
+        // validate every matching issue is checked from the input version onward
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("first", None, &["bug"]);
+        backend.add_issue("second", None, &["docs"]);
+        backend.add_issue("third", None, &["bug"]);
+        let source = source(
+            r#"
+{
+    "owner": "my_org",
+    "repo": "my_repo",
+    "query": {
+        "labels": ["bug"]
+    }
+}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let first = rt
+            .block_on(GithubIssue::check_with(&backend, &source, None))
+            .expect("the check step failed");
+        assert_eq!(
+            first,
+            vec![concourse::Version::from_issue(&backend.issue(3))],
+            "the first check did not return only the most recently updated matching issue",
+        );
+        // update the first issue so it is more recently updated than the input version
+        let gh_issue = github_issue::Issue::new(
+            None,
+            Some(String::from("updated")),
+            None,
+            None,
+            Some(1),
+            None,
             None,
-            concourse::Version::new(String::from("Open")),
             None,
-            "",
         )
         .unwrap();
+        rt.block_on(gh_issue.main(&backend, github_issue::Action::Update))
+            .expect("the issue could not be updated");
+        let versions = rt
+            .block_on(GithubIssue::check_with(
+                &backend,
+                &source,
+                first.into_iter().next(),
+            ))
+            .expect("the check step failed");
+        assert_eq!(
+            versions,
+            vec![
+                concourse::Version::from_issue(&backend.issue(3)),
+                concourse::Version::from_issue(&backend.issue(1))
+            ],
+            "the check did not return the input version and the updated issue in order",
+        );
+    }
+
+    #[test]
+    fn test_check_comments() {
+        // validate only matching comments by permitted users are checked
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &[]);
+        backend.add_org_member("member");
+        let first = backend.add_comment(1, "member", "/deploy staging");
+        backend.add_comment(1, "outsider", "/deploy production");
+        backend.add_comment(1, "member", "looks good");
+        let second = backend.add_comment(1, "reviewer", "/deploy production");
+        let source = source(
+            r#"
+{
+    "owner": "my_org",
+    "repo": "my_repo",
+    "number": 1,
+    "comment_trigger": {
+        "pattern": "^/deploy (\\w+)",
+        "users": ["reviewer"],
+        "org_members": true
+    }
+}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let versions = rt
+            .block_on(GithubIssue::check_with(
+                &backend,
+                &source,
+                Some(concourse::Version::from_comment(
+                    1,
+                    &backend.issue_comments(1)[0],
+                )),
+            ))
+            .expect("the check step failed");
+        assert_eq!(
+            versions
+                .iter()
+                .map(|version| version.comment())
+                .collect::<Vec<Option<u64>>>(),
+            vec![Some(first), Some(second)],
+            "the check did not return the comments by the member and the listed user",
+        );
+    }
+
+    #[test]
+    fn test_check_labels() {
+        // validate only the events adding the trigger label are checked
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &[]);
+        backend.add_label_event(1, "approved", true);
+        backend.add_label_event(1, "approved", false);
+        backend.add_label_event(1, "triage", true);
+        let last = backend.add_label_event(1, "approved", true);
+        let source = source(
+            r#"
+{
+    "owner": "my_org",
+    "repo": "my_repo",
+    "number": 1,
+    "label_trigger": {
+        "label": "approved"
+    }
+}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let versions = rt
+            .block_on(GithubIssue::check_with(&backend, &source, None))
+            .expect("the check step failed");
+        assert_eq!(
+            versions,
+            vec![concourse::Version::from_event(1, last)],
+            "the first check did not return only the most recent labeled event",
+        );
+    }
+
+    #[test]
+    fn test_resource_in() {
+        // validate the issue files are written to the output path for a revision version, and the comments are not retrieved by default
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &["bug"]);
+        backend.add_comment(1, "my_user", "looks good");
+        let issue = backend.issue(1);
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo", "number": 1}"#);
+        let output_path = test_dir::TestDir::new("test_resource_in");
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let in_output = rt
+            .block_on(GithubIssue::get_with(
+                &backend,
+                &source,
+                1,
+                concourse::Version::from_issue(&issue),
+                None,
+                output_path.to_str().unwrap(),
+            ))
+            .expect("the in step failed");
         assert_eq!(
             in_output.version,
+            concourse::Version::from_issue(&issue),
+            "the in step did not return the input version",
+        );
+        for (file, contents) in [
+            ("number", String::from("1")),
+            ("title", String::from("my issue")),
+            ("body", String::new()),
+            ("state", String::from("Open")),
+            ("labels", String::from("bug")),
+            ("assignees", String::new()),
+            (
+                "url",
+                String::from("https://github.example.com/my_org/my_repo/issues/1"),
+            ),
+        ] {
+            assert_eq!(
+                std::fs::read_to_string(output_path.join(file)).ok(),
+                Some(contents),
+                "the {file} file was not written to the output path",
+            );
+        }
+        let issue_json = std::fs::read_to_string(output_path.join("issue.json"))
+            .expect("the issue.json file was not written to the output path");
+        assert_eq!(
+            serde_json::from_str::<octocrab::models::issues::Issue>(&issue_json)
+                .expect("the issue.json file is not a github issue")
+                .number,
+            1,
+            "the issue.json file does not contain the issue",
+        );
+        for file in ["comments.json", "comments", "comment"] {
+            assert!(
+                !output_path.join(file).exists(),
+                "the {file} file was written without the comments param or a comment version",
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_with() {
+        // validate the issue, the triggering comment, and the comments are written to the output path
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", Some("my body"), &["bug", "triage"]);
+        let comment_id = backend.add_comment(1, "my_user", "/deploy staging");
+        let source = source(
+            r#"
+{
+    "owner": "my_org",
+    "repo": "my_repo",
+    "comment_trigger": {
+        "pattern": "^/deploy (\\w+)"
+    }
+}"#,
+        );
+        let params = serde_json::from_str::<concourse::InParams>(r#"{"comments": true}"#)
+            .expect("in params could not be deserialized");
+        let output_path = test_dir::TestDir::new("test_get_with");
+        let version = concourse::Version::from_comment(1, &backend.issue_comments(1)[0]);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let in_output = rt
+            .block_on(GithubIssue::get_with(
+                &backend,
+                &source,
+                1,
+                version,
+                Some(params),
+                output_path.to_str().unwrap(),
+            ))
+            .expect("the in step failed");
+        assert_eq!(
+            in_output.metadata,
+            Some(concourse::InMetadata::new(
+                1,
+                String::from("my issue"),
+                String::from("Open"),
+                String::from("https://github.example.com/my_org/my_repo/issues/1")
+            )),
+            "the in step did not return the issue metadata",
+        );
+        for (file, contents) in [
+            ("number", String::from("1")),
+            ("body", String::from("my body")),
+            ("labels", String::from("bug\ntriage")),
+            ("comment_captures", String::from("staging")),
+            (
+                &format!("comments/{comment_id}.md"),
+                String::from("/deploy staging"),
+            ),
+        ] {
+            assert_eq!(
+                std::fs::read_to_string(output_path.join(file)).ok(),
+                Some(contents),
+                "the {file} file was not written to the output path",
+            );
+        }
+    }
+
+    #[test]
+    fn test_out_with_create() {
+        // validate an issue is created with its assignees and a comment
+        let backend = github_issue::fake::Fake::new();
+        backend.add_assignable("my_user");
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo"}"#);
+        let params = out_params(
+            r#"
+{
+    "title": "my issue",
+    "body": "go fix it",
+    "labels": ["bug"],
+    "assignees": ["my_user"],
+    "comment": "the build failed"
+}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&backend, &source, params, ""))
+            .expect("the out step failed");
+        let issue = backend.issue(1);
+        assert_eq!(
+            out_output.version,
+            concourse::Version::from_issue(&issue),
+            "the out step did not return the version of the created issue",
+        );
+        assert_eq!(
+            issue
+                .assignees
+                .iter()
+                .map(|assignee| assignee.login.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["my_user"],
+            "the created issue was not assigned instead of labelled with the assignees",
+        );
+        assert_eq!(
+            issue
+                .labels
+                .iter()
+                .map(|label| label.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["bug"],
+            "the created issue did not have the labels",
+        );
+        assert_eq!(
+            backend.issue_comments(1)[0].body,
+            Some(String::from("the build failed")),
+            "the comment was not added to the created issue",
+        );
+
+        // validate unassignable users are reported without creating an issue
+        let params = out_params(r#"{"title": "my issue", "assignees": ["my_user", "nobody"]}"#);
+        assert_eq!(
+            rt.block_on(GithubIssue::out_with(&backend, &source, params, ""))
+                .map(|_| ()),
+            Err(Error::Validation(String::from(
+                "the following assignees cannot be assigned to issues in the repository: nobody"
+            ))),
+            "the unassignable user was not reported",
+        );
+        assert_eq!(
+            backend.issues().len(),
+            1,
+            "an issue was created with an unassignable user",
+        );
+    }
+
+    #[test]
+    fn test_out_with_create_closed() {
+        // validate the state is applied to a created issue
+        let backend = github_issue::fake::Fake::new();
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo"}"#);
+        let params = out_params(
+            r#"{"title": "my issue", "state": "Closed", "state_reason": "not_planned"}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&backend, &source, params, ""))
+            .expect("the out step failed");
+        let issue = backend.issue(1);
+        assert_eq!(
+            (issue.state.clone(), issue.state_reason.clone()),
+            (
+                octocrab::models::IssueState::Closed,
+                Some(octocrab::models::issues::IssueStateReason::NotPlanned)
+            ),
+            "the state was not applied to the created issue",
+        );
+        assert_eq!(
+            out_output.version,
+            concourse::Version::from_issue(&issue),
+            "the out step did not return the version of the closed issue",
+        );
+
+        // validate the all filter state is rejected instead of creating an open issue
+        assert_eq!(
+            rt.block_on(GithubIssue::out_with(
+                &backend,
+                &source,
+                out_params(r#"{"title": "my issue", "state": "All"}"#),
+                ""
+            ))
+            .err(),
+            Some(Error::Validation(String::from(
+                "the issue state must be either Open or Closed, and not All"
+            ))),
+            "the all state was not rejected",
+        );
+        assert_eq!(
+            backend.issues().len(),
+            1,
+            "an issue was created in the all state"
+        );
+    }
+
+    #[test]
+    fn test_out_with_dedupe() {
+        // validate an existing open issue with the marker is commented on instead of creating a duplicate
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("unrelated", None, &[]);
+        backend.add_issue(
+            "nightly build failed",
+            Some("<!-- nightly-failure -->"),
+            &["ci", "triaged"],
+        );
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo"}"#);
+        let params = out_params(
+            r#"
+{
+    "title": "nightly build failed again",
+    "labels": ["ci"],
+    "dedupe": "marker",
+    "marker": "nightly-failure",
+    "comment": "failed again"
+}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&backend, &source, params, ""))
+            .expect("the out step failed");
+        assert_eq!(
+            out_output.version.number(),
+            Some(2),
+            "the out step did not update the existing issue",
+        );
+        assert_eq!(
+            backend.issues().len(),
+            2,
+            "the out step created a duplicate issue",
+        );
+        // validate the triage of the existing issue is preserved
+        let issue = backend.issue(2);
+        assert_eq!(
+            issue.title, "nightly build failed",
+            "the title of the existing issue was replaced",
+        );
+        assert_eq!(
+            issue
+                .labels
+                .iter()
+                .map(|label| label.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["ci", "triaged"],
+            "the labels of the existing issue were replaced",
+        );
+        assert_eq!(
+            backend.issue_comments(2).len(),
+            1,
+            "the existing issue was not commented on",
+        );
+    }
+
+    #[test]
+    fn test_out_with_dedupe_labels() {
+        // validate an existing open issue with every label is updated instead of creating a duplicate
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("unrelated", None, &["bug"]);
+        backend.add_issue("flaky test", None, &["bug", "flaky"]);
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo"}"#);
+        let params = out_params(
+            r#"{"title": "flaky test", "labels": ["bug", "flaky"], "dedupe": "labels"}"#,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&backend, &source, params, ""))
+            .expect("the out step failed");
+        assert_eq!(
+            out_output.version.number(),
+            Some(2),
+            "the out step did not update the existing issue with every label",
+        );
+
+        // validate deduplicating by labels without any labels is rejected instead of matching any open issue
+        for params in [
+            r#"{"title": "flaky test", "dedupe": "labels"}"#,
+            r#"{"title": "flaky test", "labels": [], "dedupe": "labels"}"#,
+        ] {
+            assert_eq!(
+                rt.block_on(GithubIssue::out_with(
+                    &backend,
+                    &source,
+                    out_params(params),
+                    ""
+                ))
+                .err(),
+                Some(Error::Validation(String::from(
+                    "labels must be specified to deduplicate issues by labels"
+                ))),
+                "deduplicating by labels without labels was not rejected",
+            );
+        }
+        assert_eq!(
+            backend.issues().len(),
+            2,
+            "the out step created a duplicate issue",
+        );
+
+        // validate deduplication without labels is ignored when the issue number is specified
+        let out_output = rt
+            .block_on(GithubIssue::out_with(
+                &backend,
+                &source,
+                out_params(r#"{"number": 1, "title": "renamed", "dedupe": "labels"}"#),
+                "",
+            ))
+            .expect("the out step with an issue number failed");
+        assert_eq!(
+            out_output.version.number(),
+            Some(1),
+            "the out step did not update the specified issue",
+        );
+    }
+
+    #[test]
+    fn test_out_with_labels_and_milestone() {
+        // validate the label operations preserve other labels and missing labels and milestones are created
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &["pending", "external"]);
+        backend.add_milestone("v2.2");
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo", "milestone": "v2.2"}"#);
+        let params = out_params(
+            r##"
+{
+    "number": 1,
+    "add_labels": ["deployed"],
+    "remove_labels": ["pending", "absent"],
+    "create_labels": {
+        "color": "#0E8A16",
+        "description": "deployed to production"
+    },
+    "milestone": "v2.3",
+    "create_milestone": {
+        "due_on": "2026-12-01"
+    }
+}"##,
+        );
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&backend, &source, params, ""))
+            .expect("the out step failed");
+        let issue = backend.issue(1);
+        assert_eq!(
+            out_output.version,
+            concourse::Version::from_issue(&issue),
+            "the out step did not return the version after the label operations",
+        );
+        assert_eq!(
+            issue
+                .labels
+                .iter()
+                .map(|label| label.name.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["external", "deployed"],
+            "the labels were not added and removed without clobbering the others",
+        );
+        let deployed = backend
+            .repository_labels()
+            .into_iter()
+            .find(|label| label.name == "deployed")
+            .expect("the missing label was not created");
+        assert_eq!(
+            (deployed.color.as_str(), deployed.description.as_deref()),
+            ("0e8a16", Some("deployed to production")),
+            "the missing label was not created with the color and description",
+        );
+        let milestones = backend.repository_milestones();
+        assert_eq!(
+            (
+                milestones[1].title.as_str(),
+                milestones[1].due_on.map(|due_on| due_on.to_rfc3339())
+            ),
+            ("v2.3", Some(String::from("2026-12-01T00:00:00+00:00"))),
+            "the missing params milestone was not created with the due date",
+        );
+        assert_eq!(
+            issue.milestone.map(|milestone| milestone.number),
+            Some(2),
+            "the params milestone did not take precedence over the source milestone",
+        );
+    }
+
+    #[test]
+    fn test_out_with_dry_run() {
+        // validate a dry run resolves the issue but leaves the repository unmodified
+        let backend = github_issue::fake::Fake::new();
+        backend.add_issue("my issue", None, &["pending"]);
+        backend.add_assignable("my_user");
+        let source = source(r#"{"owner": "my_org", "repo": "my_repo", "number": 1}"#);
+        let params = out_params(
+            r#"
+{
+    "title": "my renamed issue",
+    "assignees": ["my_user"],
+    "add_labels": ["deployed"],
+    "remove_labels": ["pending"],
+    "create_labels": {},
+    "milestone": "v2.3",
+    "create_milestone": {},
+    "comment": "deployed to production",
+    "dry_run": true
+}"#,
+        );
+        let dry_run = github_issue::dry_run::DryRun::new(&backend, "my_org", "my_repo", None);
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&dry_run, &source, params, ""))
+            .expect("the out step dry run failed");
+        let issue = backend.issue(1);
+        assert_eq!(
+            (
+                issue.title.as_str(),
+                issue.labels.len(),
+                issue.assignees.len()
+            ),
+            ("my issue", 1, 0),
+            "the dry run updated the issue",
+        );
+        assert!(
+            backend.issue_comments(1).is_empty()
+                && backend.repository_labels().len() == 1
+                && backend.repository_milestones().is_empty(),
+            "the dry run commented or created labels or milestones",
+        );
+        let metadata = serde_json::to_value(out_output.metadata).unwrap();
+        assert_eq!(
+            (
+                &metadata["title"],
+                &metadata["labels"][0]["name"],
+                &metadata["assignees"][0]["login"],
+                &metadata["comment_id"]
+            ),
+            (
+                &serde_json::json!("my renamed issue"),
+                &serde_json::json!("deployed"),
+                &serde_json::json!("my_user"),
+                &serde_json::json!(0)
+            ),
+            "the dry run did not return synthetic metadata for the update",
+        );
+        assert_eq!(
+            out_output.version,
+            concourse::Version::from_issue(&issue),
+            "the dry run did not return the version of the unmodified issue",
+        );
+
+        // validate a dry run of a create returns a version without a number so the implicit get step is skipped
+        let params = out_params(r#"{"title": "my new issue", "dry_run": true}"#);
+        let source = self::source(r#"{"owner": "my_org", "repo": "my_repo"}"#);
+        let dry_run = github_issue::dry_run::DryRun::new(
+            &backend,
+            "my_org",
+            "my_repo",
+            Some("https://github.example.com/api/v3"),
+        );
+        let out_output = rt
+            .block_on(GithubIssue::out_with(&dry_run, &source, params, ""))
+            .expect("the out step dry run failed");
+        assert_eq!(backend.issues().len(), 1, "the dry run created the issue",);
+        assert_eq!(
+            out_output.version,
             concourse::Version::new(String::from("Open")),
-            "the resource in did not dummy the expected return version output",
+            "the dry run did not return a version without a number for the created issue",
+        );
+        // the version is emitted, but it is the version that the check step already returns for a source without a number
+        assert_eq!(
+            rt.block_on(GithubIssue::check_with(&backend, &source, None))
+                .expect("the check step failed"),
+            vec![out_output.version],
+            "the dry run of a create did not return the version of the check step",
+        );
+        assert_eq!(
+            serde_json::to_value(out_output.metadata).unwrap()["html_url"],
+            serde_json::json!("https://github.example.com/my_org/my_repo/issues/0"),
+            "the dry run did not derive the synthetic url from the api url",
+        );
+    }
+
+    #[test]
+    fn test_read_input_file() {
+        // validate file contents are read relative to the input path
+        let input_path = test_dir::TestDir::new("test_read_input_file");
+        std::fs::write(input_path.join("comment.md"), "the deployment succeeded")
+            .expect("comment file could not be written");
+        assert_eq!(
+            GithubIssue::read_input_file(input_path.to_str().unwrap(), "comment.md"),
+            Ok(String::from("the deployment succeeded")),
+            "the file contents were not read relative to the input path",
+        );
+    }
+
+    #[test]
+    fn test_read_input_list() {
+        // validate one entry is read per non-empty line
+        let input_path = test_dir::TestDir::new("test_read_input_list");
+        std::fs::write(
+            input_path.join("labels.txt"),
+            "bug\n  ci failure \n\ntriage\n",
+        )
+        .expect("labels file could not be written");
+        assert_eq!(
+            GithubIssue::read_input_list(input_path.to_str().unwrap(), "labels.txt"),
+            Ok(vec![
+                String::from("bug"),
+                String::from("ci failure"),
+                String::from("triage")
+            ]),
+            "the list entries were not read one per line",
+        );
+    }
+
+    #[test]
+    fn test_read_input_number() {
+        // validate the issue number is parsed from the file, such as the number file of a prior get step
+        let input_dir = test_dir::TestDir::new("test_read_input_number");
+        std::fs::create_dir_all(input_dir.join("my-issue"))
+            .expect("input path could not be created");
+        std::fs::write(input_dir.join("my-issue/number"), "42\n")
+            .expect("number file could not be written");
+        std::fs::write(input_dir.join("my-issue/title"), "my issue")
+            .expect("title file could not be written");
+        let input_path = input_dir.to_str().unwrap();
+        assert_eq!(
+            GithubIssue::read_input_number(input_path, "my-issue/number"),
+            Ok(42),
+            "the issue number was not read from the file",
+        );
+        assert!(
+            matches!(
+                GithubIssue::read_input_number(input_path, "my-issue/title"),
+                Err(Error::Validation(_))
+            ),
+            "a file without an issue number was not reported",
         );
     }
 }
@@ -0,0 +1,306 @@
+//! # Retry
+//!
+//! `retry` contains the policy for retrying failed Github API requests with exponential backoff and jitter, and for waiting on an exceeded rate limit until it resets.
+
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// github recommends waiting at least one minute after exceeding a secondary rate limit without a retry-after header
+const SECONDARY_RATE_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+// the unparsed response to a github api request
+pub(crate) type Response =
+    http::Response<http_body_util::combinators::BoxBody<bytes::Bytes, octocrab::Error>>;
+
+// how a failed request is retried
+#[derive(Eq, PartialEq, Debug)]
+enum Failure {
+    // server errors and network failures are retried with backoff
+    Transient,
+    // exceeded rate limits are retried after the rate limit resets
+    RateLimit,
+    // every other failure would fail again
+    Permanent,
+}
+
+fn classify(error: &octocrab::Error, headers: &http::HeaderMap) -> Failure {
+    match error {
+        octocrab::Error::GitHub { source, .. } => match source.status_code.as_u16() {
+            403 | 429
+                if source.message.to_lowercase().contains("rate limit")
+                    || header_wait(headers, 0).is_some() =>
+            {
+                Failure::RateLimit
+            }
+            status if status >= 500 => Failure::Transient,
+            _ => Failure::Permanent,
+        },
+        octocrab::Error::Service { .. }
+        | octocrab::Error::Hyper { .. }
+        | octocrab::Error::Http { .. } => Failure::Transient,
+        _ => Failure::Permanent,
+    }
+}
+
+// the wait specified by the rate limit headers of a response: the retry-after of a secondary rate limit, or otherwise the x-ratelimit-reset of an exhausted primary rate limit
+fn header_wait(headers: &http::HeaderMap, now: u64) -> Option<Duration> {
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok())
+    };
+    if let Some(retry_after) = header("retry-after") {
+        return Some(Duration::from_secs(retry_after));
+    }
+    match (header("x-ratelimit-remaining"), header("x-ratelimit-reset")) {
+        (Some(0), Some(reset)) => Some(Duration::from_secs(reset.saturating_sub(now) + 1)),
+        _ => None,
+    }
+}
+
+// a brief description of a retried failure because the octocrab display of some failures includes a backtrace
+fn describe(error: &octocrab::Error) -> String {
+    match error {
+        octocrab::Error::GitHub { source, .. } => {
+            format!("{} {}", source.status_code, source.message)
+        }
+        octocrab::Error::Service { source, .. } => source.to_string(),
+        octocrab::Error::Hyper { source, .. } => source.to_string(),
+        octocrab::Error::Http { source, .. } => source.to_string(),
+        _ => String::from("unknown failure"),
+    }
+}
+
+// random value for jitter without an additional dependency
+fn random() -> u64 {
+    std::collections::hash_map::RandomState::new()
+        .build_hasher()
+        .finish()
+}
+
+// policy for retrying failed github api requests
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub(crate) struct RetryPolicy {
+    // the number of retries after the first attempt
+    retries: u32,
+    // the backoff before the first retry, which doubles with each subsequent retry
+    backoff: Duration,
+    max_backoff: Duration,
+    // the longest wait for an exceeded rate limit to reset before failing instead
+    max_rate_limit_wait: Duration,
+}
+
+impl RetryPolicy {
+    /// Constructor for the RetryPolicy struct. The durations are expressed in seconds, and default to a backoff of 1 second up to 60 seconds for 3 retries, and a rate limit wait of up to 900 seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let policy = RetryPolicy::new(Some(5), Some(2), None, Some(3600));
+    /// ```
+    pub(crate) fn new(
+        retries: Option<u32>,
+        backoff: Option<u64>,
+        max_backoff: Option<u64>,
+        max_rate_limit_wait: Option<u64>,
+    ) -> Self {
+        Self {
+            retries: retries.unwrap_or(3),
+            backoff: Duration::from_secs(backoff.unwrap_or(1)),
+            max_backoff: Duration::from_secs(max_backoff.unwrap_or(60)),
+            max_rate_limit_wait: Duration::from_secs(max_rate_limit_wait.unwrap_or(900)),
+        }
+    }
+
+    /// Send the request returned by the input function, and send it again according to the policy if it fails with an exceeded rate limit, or with a server error or a network failure unless it is a POST. A POST may have been processed by github before it failed, and so resending it could create a duplicate issue, comment, label, or milestone. The github app installation token is obtained by octocrab within the request, and so a failure to obtain it is retried according to the policy even for a POST, which was not yet sent. Unsuccessful responses are returned as errors.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let response = policy.send(&http::Method::GET, || client.execute(request())).await?;
+    /// ```
+    pub(crate) async fn send<F, Fut>(
+        &self,
+        method: &http::Method,
+        mut request: F,
+    ) -> octocrab::Result<Response>
+    where
+        F: FnMut() -> Fut,
+        Fut: std::future::Future<Output = octocrab::Result<Response>>,
+    {
+        let mut attempt: u32 = 0;
+        loop {
+            // the headers are retained because the github error does not include them
+            let (error, headers, sent) = match request().await {
+                Ok(response) => {
+                    let headers = response.headers().clone();
+                    match octocrab::map_github_error(response).await {
+                        Ok(response) => return Ok(response),
+                        Err(error) => (error, headers, true),
+                    }
+                }
+                // octocrab only returns a github error before sending the request, when the github app installation token could not be obtained
+                Err(error) => {
+                    let sent = !matches!(error, octocrab::Error::GitHub { .. });
+                    (error, http::HeaderMap::new(), sent)
+                }
+            };
+            if attempt >= self.retries {
+                return Err(error);
+            }
+            let wait = match classify(&error, &headers) {
+                Failure::Transient if *method != http::Method::POST || !sent => {
+                    self.backoff(attempt)
+                }
+                // github does not process a request rejected by a rate limit
+                Failure::RateLimit => match self.rate_limit_wait(&headers, attempt) {
+                    Some(wait) => wait,
+                    // the rate limit resets too far in the future to wait
+                    None => return Err(error),
+                },
+                _ => return Err(error),
+            };
+            eprintln!(
+                "the github api request failed with {}, and will be retried in {:.1} seconds",
+                describe(&error),
+                wait.as_secs_f64()
+            );
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    // exponential backoff with equal jitter so concurrent checks sharing a token do not retry in lockstep
+    fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .backoff
+            .saturating_mul(2_u32.saturating_pow(attempt))
+            .min(self.max_backoff);
+        let half = backoff / 2;
+        let jitter_millis = match half.as_millis() as u64 {
+            0 => 0,
+            millis => random() % millis,
+        };
+        half + Duration::from_millis(jitter_millis)
+    }
+
+    // wait until the primary rate limit resets, or for a secondary rate limit to subside, according to the response headers
+    fn rate_limit_wait(&self, headers: &http::HeaderMap, attempt: u32) -> Option<Duration> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        // otherwise a secondary rate limit was exceeded without a retry-after header
+        let wait = header_wait(headers, now)
+            .unwrap_or_else(|| SECONDARY_RATE_LIMIT_WAIT.max(self.backoff(attempt)));
+        if wait > self.max_rate_limit_wait {
+            eprintln!(
+                "the github api rate limit resets in {} seconds, which exceeds the max_rate_limit_wait of {} seconds",
+                wait.as_secs(),
+                self.max_rate_limit_wait.as_secs()
+            );
+            return None;
+        }
+
+        Some(wait)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(None, None, None, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_policy_new() {
+        assert_eq!(
+            RetryPolicy::new(Some(5), None, Some(30), None),
+            RetryPolicy {
+                retries: 5,
+                backoff: Duration::from_secs(1),
+                max_backoff: Duration::from_secs(30),
+                max_rate_limit_wait: Duration::from_secs(900),
+            },
+            "failed to construct RetryPolicy with defaults"
+        );
+    }
+
+    #[test]
+    fn test_retry_policy_backoff() {
+        // validates the backoff doubles with each retry within the jitter, and is capped at the max backoff
+        let policy = RetryPolicy::new(None, Some(2), Some(10), None);
+        for (attempt, max) in [(0, 2), (1, 4), (2, 8), (3, 10), (10, 10)] {
+            let backoff = policy.backoff(attempt);
+            assert!(
+                backoff >= Duration::from_secs(max) / 2 && backoff <= Duration::from_secs(max),
+                "backoff for attempt {attempt} was {backoff:?} instead of between half of and {max} seconds"
+            );
+        }
+        assert_eq!(
+            RetryPolicy::new(None, Some(0), None, None).backoff(3),
+            Duration::ZERO,
+            "zero backoff was not zero"
+        );
+    }
+
+    #[test]
+    fn test_header_wait() {
+        // validates the retry-after header takes precedence over the primary rate limit reset
+        let headers = |pairs: &[(&'static str, &'static str)]| {
+            pairs
+                .iter()
+                .map(|(name, value)| {
+                    (
+                        http::HeaderName::from_static(name),
+                        http::HeaderValue::from_static(value),
+                    )
+                })
+                .collect::<http::HeaderMap>()
+        };
+        assert_eq!(
+            header_wait(&headers(&[("retry-after", "30")]), 1_000),
+            Some(Duration::from_secs(30)),
+            "retry-after header did not set the wait"
+        );
+        assert_eq!(
+            header_wait(
+                &headers(&[
+                    ("retry-after", "30"),
+                    ("x-ratelimit-remaining", "0"),
+                    ("x-ratelimit-reset", "1120")
+                ]),
+                1_000
+            ),
+            Some(Duration::from_secs(30)),
+            "retry-after header did not take precedence over the rate limit reset"
+        );
+        assert_eq!(
+            header_wait(
+                &headers(&[
+                    ("x-ratelimit-remaining", "0"),
+                    ("x-ratelimit-reset", "1120")
+                ]),
+                1_000
+            ),
+            Some(Duration::from_secs(121)),
+            "x-ratelimit-reset header did not set the wait until the reset"
+        );
+        assert_eq!(
+            header_wait(
+                &headers(&[
+                    ("x-ratelimit-remaining", "10"),
+                    ("x-ratelimit-reset", "1120")
+                ]),
+                1_000
+            ),
+            None,
+            "x-ratelimit-reset header set the wait although the rate limit was not exhausted"
+        );
+    }
+}
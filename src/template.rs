@@ -0,0 +1,135 @@
+//! # Template
+//!
+//! `template` renders the title and body of an issue with the Concourse build metadata and the contents of files in the input directory of the out/put step.
+
+use crate::error::Error;
+
+// the concourse build metadata available to templates as environment variables
+const BUILD_VARIABLES: [&str; 6] = [
+    "BUILD_ID",
+    "BUILD_NAME",
+    "BUILD_JOB_NAME",
+    "BUILD_PIPELINE_NAME",
+    "BUILD_TEAM_NAME",
+    "ATC_EXTERNAL_URL",
+];
+
+/// Render the template with the Concourse build metadata from the environment, and with the files relative to the input path. Variables are expressed as `${BUILD_ID}` etc., the derived `${BUILD_URL}` links to the build, and files are expressed as `${file:path/to/file}`. Any other text is unchanged.
+///
+/// # Examples
+///
+/// ```
+/// let title = render("${BUILD_JOB_NAME} build ${BUILD_NAME} failed", "/tmp/build/put")?;
+/// ```
+pub(crate) fn render(template: &str, input_path: &str) -> Result<String, Error> {
+    render_with(template, input_path, |name| std::env::var(name).ok())
+}
+
+// render the template with the variables from the input lookup
+fn render_with(
+    template: &str,
+    input_path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, Error> {
+    let pattern = regex::Regex::new(&format!(
+        r"\$\{{(file:[^}}]+|BUILD_URL|{})\}}",
+        BUILD_VARIABLES.join("|")
+    ))
+    .expect("the template pattern is a valid regular expression");
+    let variable = |name: &str| lookup(name).unwrap_or_default();
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut last = 0;
+    for captures in pattern.captures_iter(template) {
+        let placeholder = captures.get(0).expect("the match is always captured");
+        let name = &captures[1];
+        rendered.push_str(&template[last..placeholder.start()]);
+        match name.strip_prefix("file:") {
+            Some(file) => {
+                let file_path = format!("{input_path}/{file}");
+                let contents = std::fs::read_to_string(&file_path).map_err(|error| {
+                    Error::Io(format!(
+                        "the file {file_path} in the template could not be read: {error}"
+                    ))
+                })?;
+                rendered.push_str(contents.trim_end());
+            }
+            None if name == "BUILD_URL" => rendered.push_str(&format!(
+                "{}/teams/{}/pipelines/{}/jobs/{}/builds/{}",
+                variable("ATC_EXTERNAL_URL"),
+                variable("BUILD_TEAM_NAME"),
+                variable("BUILD_PIPELINE_NAME"),
+                variable("BUILD_JOB_NAME"),
+                variable("BUILD_NAME"),
+            )),
+            None => rendered.push_str(&variable(name)),
+        }
+        last = placeholder.end();
+    }
+    rendered.push_str(&template[last..]);
+
+    Ok(rendered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "BUILD_ID" => Some(String::from("1234")),
+            "BUILD_NAME" => Some(String::from("42")),
+            "BUILD_JOB_NAME" => Some(String::from("deploy")),
+            "BUILD_PIPELINE_NAME" => Some(String::from("my-pipeline")),
+            "BUILD_TEAM_NAME" => Some(String::from("main")),
+            "ATC_EXTERNAL_URL" => Some(String::from("https://ci.example.com")),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_render_with_variables() {
+        assert_eq!(
+            render_with(
+                "${BUILD_PIPELINE_NAME}/${BUILD_JOB_NAME} build ${BUILD_NAME} (${BUILD_ID}) failed",
+                "",
+                lookup
+            ),
+            Ok(String::from("my-pipeline/deploy build 42 (1234) failed")),
+            "build metadata variables were not rendered"
+        );
+        assert_eq!(
+            render_with("see ${BUILD_URL}", "", lookup),
+            Ok(String::from(
+                "see https://ci.example.com/teams/main/pipelines/my-pipeline/jobs/deploy/builds/42"
+            )),
+            "build url was not rendered"
+        );
+        assert_eq!(
+            render_with("echo ${HOME} $BUILD_ID", "", lookup),
+            Ok(String::from("echo ${HOME} $BUILD_ID")),
+            "text other than the template variables was changed"
+        );
+    }
+
+    #[test]
+    fn test_render_with_file() {
+        let input_dir = crate::test_dir::TestDir::new("test_render_with_file");
+        std::fs::create_dir_all(input_dir.join("logs")).expect("input path could not be created");
+        std::fs::write(input_dir.join("logs/error.txt"), "connection refused\n")
+            .expect("template file could not be written");
+        let input_path = input_dir.to_str().unwrap();
+        assert_eq!(
+            render_with("```\n${file:logs/error.txt}\n```", input_path, lookup),
+            Ok(String::from("```\nconnection refused\n```")),
+            "file contents were not rendered"
+        );
+        assert!(
+            matches!(
+                render_with("${file:logs/missing.txt}", input_path, lookup),
+                Err(Error::Io(_))
+            ),
+            "missing file was not reported"
+        );
+    }
+}
@@ -0,0 +1,41 @@
+//! # Test Dir
+//!
+//! `test_dir` contains the temporary directory for tests that read or write files. The directory is unique to the test and the test process, and is removed once the test finishes.
+
+// a temporary directory that is removed when dropped, including when the test fails
+pub(crate) struct TestDir {
+    path: std::path::PathBuf,
+}
+
+impl TestDir {
+    /// Constructor for the TestDir struct. Creates an empty directory named after the test and the test process so that concurrent test runs do not share state.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// let input_path = TestDir::new("test_read_input_file");
+    /// ```
+    pub(crate) fn new(name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "concourse-github-issue-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("test directory could not be created");
+        Self { path }
+    }
+}
+
+impl std::ops::Deref for TestDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.path
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
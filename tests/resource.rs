@@ -0,0 +1,499 @@
+//! Integration tests that execute the resource binary as the Concourse check, in, and out steps against a local stand-in for the Github REST API serving recorded responses.
+#![cfg(unix)]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::os::unix::process::CommandExt;
+use std::sync::{Arc, Mutex};
+
+// a recorded response for a method and path, ignoring the query
+struct Route {
+    method: &'static str,
+    path: &'static str,
+    status: &'static str,
+    body: String,
+}
+
+impl Route {
+    fn new(method: &'static str, path: &'static str, status: &'static str, body: String) -> Self {
+        Route {
+            method,
+            path,
+            status,
+            body,
+        }
+    }
+}
+
+// a request received by the local github api
+#[derive(Debug)]
+struct Request {
+    line: String,
+    body: String,
+}
+
+// serve the routes on a local port, one request per connection, and return the base url and the received requests
+// the server is never stopped because it only lives as long as the test process
+fn github_api(routes: Vec<Route>) -> (String, Arc<Mutex<Vec<Request>>>) {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let received = Arc::clone(&requests);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            // read the request line, the content length header, and then the body
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut length = 0;
+            let mut header = String::new();
+            while reader.read_line(&mut header).unwrap() > 2 {
+                if let Some((name, value)) = header.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap();
+                    }
+                }
+                header.clear();
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            // respond with the recorded response for the route, and otherwise with the github not found response
+            let mut parts = line.split_whitespace();
+            let method = parts.next().unwrap_or_default();
+            let path = parts
+                .next()
+                .unwrap_or_default()
+                .split('?')
+                .next()
+                .unwrap_or_default();
+            let (status, response) = routes
+                .iter()
+                .find(|route| route.method == method && route.path == path)
+                .map(|route| (route.status, route.body.as_str()))
+                .unwrap_or((
+                    "404 Not Found",
+                    r#"{"message":"Not Found","documentation_url":"https://docs.github.com/rest"}"#,
+                ));
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                response.len()
+            )
+            .unwrap();
+            received.lock().unwrap().push(Request {
+                line: line.trim_end().to_string(),
+                body: String::from_utf8(body).unwrap(),
+            });
+        }
+    });
+    (url, requests)
+}
+
+// read a recorded github api response from the fixtures
+fn fixture(name: &str) -> String {
+    std::fs::read_to_string(format!(
+        "{}/tests/fixtures/{name}",
+        env!("CARGO_MANIFEST_DIR")
+    ))
+    .unwrap()
+}
+
+// an empty directory for the in or out step, which is unique to the test process and removed when dropped
+struct StepDir {
+    path: String,
+}
+
+impl std::ops::Deref for StepDir {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl Drop for StepDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
+
+// create the directory for the step
+fn step_dir(name: &str) -> StepDir {
+    let path = std::env::temp_dir().join(format!(
+        "concourse-github-issue-{name}-{}",
+        std::process::id()
+    ));
+    let _ = std::fs::remove_dir_all(&path);
+    std::fs::create_dir_all(&path).unwrap();
+    StepDir {
+        path: path.to_str().unwrap().to_string(),
+    }
+}
+
+// execute the resource binary as the step with the input on stdin, and return the exit status, stdout, and stderr
+fn step(
+    name: &str,
+    args: &[&str],
+    envs: &[(&str, &str)],
+    input: serde_json::Value,
+) -> (std::process::ExitStatus, String, String) {
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_concourse-github-issue"))
+        .arg0(format!("/opt/resource/{name}"))
+        .args(args)
+        .envs(envs.iter().copied())
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.to_string().as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    (
+        output.status,
+        String::from_utf8(output.stdout).unwrap(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+#[test]
+fn test_check() {
+    // validate the state of the open issue is checked
+    let (url, requests) = github_api(vec![Route::new(
+        "GET",
+        "/repos/my_org/my_repo/issues/5",
+        "200 OK",
+        fixture("issue.json"),
+    )]);
+    let (status, stdout, stderr) = step(
+        "check",
+        &[],
+        &[],
+        serde_json::json!({
+            "source": {"owner": "my_org", "repo": "my_repo", "number": 5, "api_url": url},
+        }),
+    );
+    assert!(status.success(), "check step failed: {stderr}");
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&stdout).unwrap(),
+        serde_json::json!([{"state": "Open", "number": "5"}]),
+        "open state of the issue not output as the version",
+    );
+    assert_eq!(
+        requests.lock().unwrap()[0].line,
+        "GET /repos/my_org/my_repo/issues/5 HTTP/1.1",
+        "issue not requested from the api url",
+    );
+}
+
+#[test]
+fn test_check_not_found() {
+    // validate a missing issue fails the check step
+    let (url, _) = github_api(Vec::new());
+    let (status, stdout, stderr) = step(
+        "check",
+        &[],
+        &[],
+        serde_json::json!({
+            "source": {"owner": "my_org", "repo": "my_repo", "number": 5, "api_url": url},
+            "version": null,
+        }),
+    );
+    assert_eq!(
+        status.code(),
+        Some(1),
+        "missing issue did not fail the step"
+    );
+    assert!(stdout.is_empty(), "failed step output a version");
+    assert!(
+        stderr.contains("Error!") && stderr.contains("the issue number 5"),
+        "missing issue not reported: {stderr}",
+    );
+}
+
+#[test]
+fn test_in() {
+    // validate the issue and its comments are written to the output directory
+    let (url, requests) = github_api(vec![
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/issues/5",
+            "200 OK",
+            fixture("issue.json"),
+        ),
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/issues/5/comments",
+            "200 OK",
+            fixture("comments.json"),
+        ),
+    ]);
+    let output_path = step_dir("test_in");
+    let (status, stdout, stderr) = step(
+        "in",
+        &[&output_path],
+        &[],
+        serde_json::json!({
+            "source": {"owner": "my_org", "repo": "my_repo", "number": 5, "api_url": url},
+            "version": {"state": "Open", "number": "5", "updated_at": "2024-01-02T12:00:00Z"},
+            "params": {"comments": true},
+        }),
+    );
+    assert!(status.success(), "in step failed: {stderr}");
+    let output = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+    assert_eq!(
+        output["version"],
+        serde_json::json!({"state": "Open", "number": "5", "updated_at": "2024-01-02T12:00:00Z"}),
+        "input version not output",
+    );
+    assert!(
+        output["metadata"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!({"name": "title", "value": "deploy failed"})),
+        "issue title not output in the metadata: {output}",
+    );
+    for (file, contents) in [
+        ("number", "5"),
+        ("title", "deploy failed"),
+        ("body", "the deploy to production failed"),
+        ("state", "Open"),
+        ("labels", "bug"),
+        ("assignees", "octocat"),
+        ("url", "https://github.com/my_org/my_repo/issues/5"),
+        ("comments/1002.md", "fixed in the next release"),
+    ] {
+        assert_eq!(
+            std::fs::read_to_string(format!("{}/{file}", &*output_path)).unwrap(),
+            contents,
+            "issue file {file} not written",
+        );
+    }
+    assert_eq!(
+        requests.lock().unwrap().len(),
+        2,
+        "issue and comments not requested exactly once each",
+    );
+}
+
+#[test]
+fn test_out() {
+    // validate the issue is created with the rendered title, assigned, and commented
+    let (url, requests) = github_api(vec![
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/assignees/octocat",
+            "204 No Content",
+            String::new(),
+        ),
+        Route::new(
+            "POST",
+            "/repos/my_org/my_repo/issues",
+            "201 Created",
+            fixture("issue_created.json"),
+        ),
+        Route::new(
+            "POST",
+            "/repos/my_org/my_repo/issues/6/comments",
+            "201 Created",
+            fixture("comment_created.json"),
+        ),
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/issues/6",
+            "200 OK",
+            fixture("issue_commented.json"),
+        ),
+    ]);
+    let input_path = step_dir("test_out");
+    let (status, stdout, stderr) = step(
+        "out",
+        &[&input_path],
+        &[("BUILD_NAME", "42")],
+        serde_json::json!({
+            "source": {"owner": "my_org", "repo": "my_repo", "api_url": url},
+            "params": {
+                "title": "build ${BUILD_NAME} failed",
+                "body": "see the build logs",
+                "labels": ["bug"],
+                "assignees": ["octocat"],
+                "comment": "first seen in build 42",
+            },
+        }),
+    );
+    assert!(status.success(), "out step failed: {stderr}");
+    let output = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+    assert_eq!(
+        output["version"],
+        serde_json::json!({"state": "Open", "number": "6", "updated_at": "2024-01-03T00:00:01Z"}),
+        "commented revision of the created issue not output as the version",
+    );
+    let metadata = output["metadata"].as_array().unwrap();
+    for (name, value) in [
+        ("number", "6"),
+        ("html_url", "https://github.com/my_org/my_repo/issues/6"),
+        ("comment_id", "1003"),
+    ] {
+        assert!(
+            metadata.contains(&serde_json::json!({"name": name, "value": value})),
+            "{name} not output in the metadata: {output}",
+        );
+    }
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(
+        requests
+            .iter()
+            .map(|request| request.line.as_str())
+            .collect::<Vec<&str>>(),
+        vec![
+            "GET /repos/my_org/my_repo/assignees/octocat HTTP/1.1",
+            "POST /repos/my_org/my_repo/issues HTTP/1.1",
+            "POST /repos/my_org/my_repo/issues/6/comments HTTP/1.1",
+            "GET /repos/my_org/my_repo/issues/6 HTTP/1.1",
+        ],
+        "issue not validated, created, commented, and read in order",
+    );
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&requests[1].body).unwrap(),
+        serde_json::json!({
+            "title": "build 42 failed",
+            "body": "see the build logs",
+            "labels": ["bug"],
+            "assignees": ["octocat"],
+        }),
+        "issue not created with the rendered title",
+    );
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&requests[2].body).unwrap(),
+        serde_json::json!({"body": "first seen in build 42"}),
+        "comment not created with the body",
+    );
+}
+
+#[test]
+fn test_out_comment() {
+    // validate a comment on an existing issue without any change to the issue does not update the issue
+    let (url, requests) = github_api(vec![
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/issues/5",
+            "200 OK",
+            fixture("issue.json"),
+        ),
+        Route::new(
+            "POST",
+            "/repos/my_org/my_repo/issues/5/comments",
+            "201 Created",
+            fixture("comment_created.json"),
+        ),
+    ]);
+    let input_path = step_dir("test_out_comment");
+    let (status, stdout, stderr) = step(
+        "out",
+        &[&input_path],
+        &[],
+        serde_json::json!({
+            "source": {"owner": "my_org", "repo": "my_repo", "api_url": url},
+            "params": {"number": 5, "comment": "deployed in build 42"},
+        }),
+    );
+    assert!(status.success(), "out step failed: {stderr}");
+    let output = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+    assert!(
+        output["metadata"]
+            .as_array()
+            .unwrap()
+            .contains(&serde_json::json!({"name": "comment_id", "value": "1003"})),
+        "comment_id not output in the metadata: {output}",
+    );
+    assert_eq!(
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.line.as_str())
+            .collect::<Vec<&str>>(),
+        vec![
+            "GET /repos/my_org/my_repo/issues/5 HTTP/1.1",
+            "POST /repos/my_org/my_repo/issues/5/comments HTTP/1.1",
+            "GET /repos/my_org/my_repo/issues/5 HTTP/1.1",
+        ],
+        "issue not read and commented without an update",
+    );
+}
+
+#[test]
+fn test_out_dry_run() {
+    // validate a dry run only reads from the github api, and prints the modifications instead
+    let (url, requests) = github_api(vec![
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/issues/5",
+            "200 OK",
+            fixture("issue.json"),
+        ),
+        Route::new(
+            "GET",
+            "/repos/my_org/my_repo/labels",
+            "200 OK",
+            String::from("[]"),
+        ),
+    ]);
+    let input_path = step_dir("test_out_dry_run");
+    let (status, stdout, stderr) = step(
+        "out",
+        &[&input_path],
+        &[],
+        serde_json::json!({
+            "source": {"owner": "my_org", "repo": "my_repo", "number": 5, "api_url": url},
+            "params": {
+                "state": "Closed",
+                "state_reason": "completed",
+                "add_labels": ["needs triage"],
+                "create_labels": {},
+                "comment": "fixed in the next release",
+                "dry_run": true,
+            },
+        }),
+    );
+    assert!(status.success(), "out step dry run failed: {stderr}");
+    assert_eq!(
+        requests
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|request| request.line.as_str())
+            .filter(|line| !line.starts_with("GET "))
+            .collect::<Vec<&str>>(),
+        Vec::<&str>::new(),
+        "dry run sent a modification to the github api",
+    );
+    for request in [
+        "POST /repos/my_org/my_repo/labels\n{\n  \"color\": \"ededed\",\n  \"description\": \"\",\n  \"name\": \"needs triage\"\n}",
+        "PATCH /repos/my_org/my_repo/issues/5\n{\n  \"state\": \"closed\",\n  \"state_reason\": \"completed\"\n}",
+        "POST /repos/my_org/my_repo/issues/5/labels\n{\n  \"labels\": [\n    \"needs triage\"\n  ]\n}",
+        "POST /repos/my_org/my_repo/issues/5/comments\n{\n  \"body\": \"fixed in the next release\"\n}",
+    ] {
+        assert!(
+            stderr.contains(request),
+            "dry run did not print the request {request}: {stderr}",
+        );
+    }
+    let output = serde_json::from_str::<serde_json::Value>(&stdout).unwrap();
+    assert_eq!(
+        output["version"],
+        serde_json::json!({"number": "5", "state": "Open", "updated_at": "2024-01-02T12:00:00Z"}),
+        "current revision of the issue not output as the version: {output}",
+    );
+    assert_eq!(
+        &output["metadata"][2],
+        &serde_json::json!({"name": "state", "value": "Closed"}),
+        "synthetic revision of the issue not output as the metadata: {output}",
+    );
+}